@@ -0,0 +1,365 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use semver::{Version, VersionReq};
+
+use crate::lockfile::{self, Ecosystem, LockedPackage, Lockfile};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("Advisory database not found at {0:?}")]
+    InvalidDatabase(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct AdvisorySources {
+    pub rustsec: Option<PathBuf>,
+    pub npm: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Vulnerability {
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    pub version: String,
+    pub lockfile: String,
+    pub advisory: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub severity: Option<String>,
+    pub informational: Option<String>,
+    pub patched: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct YankedPackage {
+    pub package: String,
+    pub version: String,
+    pub lockfile: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UnauditedLockfile {
+    pub ecosystem: Ecosystem,
+    pub lockfile: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct AuditReport {
+    pub lockfiles: Vec<String>,
+    pub packages: usize,
+    pub vulnerabilities: Vec<Vulnerability>,
+    pub yanked: Vec<YankedPackage>,
+    pub unaudited: Vec<UnauditedLockfile>,
+}
+
+#[derive(Debug, Clone)]
+enum Affected {
+    Except(Vec<VersionReq>),
+    Within(Vec<VersionReq>),
+}
+
+#[derive(Debug, Clone)]
+struct Advisory {
+    id: String,
+    title: Option<String>,
+    url: Option<String>,
+    severity: Option<String>,
+    informational: Option<String>,
+    patched: Vec<String>,
+    affected: Affected,
+}
+
+impl Advisory {
+    fn affects(&self, version: &Version) -> bool {
+        match &self.affected {
+            Affected::Except(reqs) => !reqs.iter().any(|r| r.matches(version)),
+            Affected::Within(reqs) => reqs.iter().any(|r| r.matches(version)),
+        }
+    }
+}
+
+pub struct AdvisoryDatabase {
+    rustsec: Option<HashMap<String, Vec<Advisory>>>,
+    npm: Option<HashMap<String, Vec<Advisory>>>,
+    registry_caches: Vec<PathBuf>,
+}
+
+impl AdvisoryDatabase {
+    pub fn load(sources: &AdvisorySources) -> Result<Self, Error> {
+        let rustsec = match &sources.rustsec {
+            Some(path) => Some(load_rustsec(path)?),
+            None => None,
+        };
+        let npm = match &sources.npm {
+            Some(path) => Some(load_npm(path)?),
+            None => None,
+        };
+        Ok(Self {
+            rustsec,
+            npm,
+            registry_caches: registry_caches(),
+        })
+    }
+
+    pub fn audit(&self, path: &Path) -> AuditReport {
+        let mut report = AuditReport::default();
+        for lockfile in lockfile::lockfiles(path) {
+            self.check(&lockfile, &mut report);
+        }
+        report
+    }
+
+    fn check(&self, lockfile: &Lockfile, report: &mut AuditReport) {
+        let ecosystem = lockfile.ecosystem;
+        let advisories = match ecosystem {
+            Ecosystem::Cargo => &self.rustsec,
+            Ecosystem::Npm => &self.npm,
+        };
+        let advisories = match advisories {
+            Some(a) => a,
+            None => {
+                report.unaudited.push(UnauditedLockfile {
+                    ecosystem,
+                    lockfile: lockfile.path.clone(),
+                });
+                return;
+            }
+        };
+        report.lockfiles.push(lockfile.path.clone());
+        report.packages += lockfile.packages.len();
+        for package in lockfile.packages.iter() {
+            let version = match Version::parse(&package.version) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            for advisory in advisories.get(&package.name).into_iter().flatten() {
+                if !advisory.affects(&version) {
+                    continue;
+                }
+                report.vulnerabilities.push(Vulnerability {
+                    ecosystem,
+                    package: package.name.clone(),
+                    version: package.version.clone(),
+                    lockfile: lockfile.path.clone(),
+                    advisory: advisory.id.clone(),
+                    title: advisory.title.clone(),
+                    url: advisory.url.clone(),
+                    severity: advisory.severity.clone(),
+                    informational: advisory.informational.clone(),
+                    patched: advisory.patched.clone(),
+                });
+            }
+            if ecosystem == Ecosystem::Cargo && package.is_registry() && self.is_yanked(package) {
+                report.yanked.push(YankedPackage {
+                    package: package.name.clone(),
+                    version: package.version.clone(),
+                    lockfile: lockfile.path.clone(),
+                });
+            }
+        }
+    }
+
+    fn is_yanked(&self, package: &LockedPackage) -> bool {
+        let relative = index_path(&package.name.to_lowercase());
+        for cache in self.registry_caches.iter() {
+            let content = match fs::read(cache.join(&relative)) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            for entry in content.split(|b| *b == 0) {
+                if !entry.starts_with(b"{") {
+                    continue;
+                }
+                let json = match serde_json::from_slice::<serde_json::Value>(entry) {
+                    Ok(j) => j,
+                    Err(_) => continue,
+                };
+                if json["vers"].as_str() == Some(package.version.as_str()) {
+                    return json["yanked"].as_bool().unwrap_or(false);
+                }
+            }
+        }
+        false
+    }
+}
+
+fn load_rustsec(path: &Path) -> Result<HashMap<String, Vec<Advisory>>, Error> {
+    let crates = path.join("crates");
+    if !crates.is_dir() {
+        return Err(Error::InvalidDatabase(path.to_path_buf()));
+    }
+    let mut advisories: HashMap<String, Vec<Advisory>> = HashMap::new();
+    for dir in fs::read_dir(&crates)?.flatten() {
+        if !dir.path().is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(dir.path())?.flatten() {
+            let path = file.path();
+            if path.extension().map(|e| e != "md").unwrap_or(true) {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            match parse_rustsec(&content) {
+                Some((package, advisory)) => advisories.entry(package).or_default().push(advisory),
+                None => log::warn!("[load_rustsec] skipping {:?}", path),
+            }
+        }
+    }
+    Ok(advisories)
+}
+
+fn parse_rustsec(content: &str) -> Option<(String, Advisory)> {
+    let front = content.trim_start().strip_prefix("```toml")?;
+    let (front, body) = front.split_once("```")?;
+    let document = front.parse::<toml_edit::Document>().ok()?;
+    let advisory = document.get("advisory")?;
+    if advisory.get("withdrawn").is_some() {
+        return None;
+    }
+    let string = |item: Option<&toml_edit::Item>| item.and_then(|i| i.as_str()).map(String::from);
+    let list = |key: &str| {
+        document
+            .get("versions")
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default()
+    };
+    let patched = list("patched");
+    let safe = patched
+        .iter()
+        .chain(list("unaffected").iter())
+        .filter_map(|r| VersionReq::parse(r).ok())
+        .collect();
+    let title = body
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("# "))
+        .map(String::from);
+
+    Some((
+        advisory.get("package")?.as_str()?.to_string(),
+        Advisory {
+            id: advisory.get("id")?.as_str()?.to_string(),
+            title,
+            url: string(advisory.get("url")),
+            severity: None,
+            informational: string(advisory.get("informational")),
+            patched,
+            affected: Affected::Except(safe),
+        },
+    ))
+}
+
+fn load_npm(path: &Path) -> Result<HashMap<String, Vec<Advisory>>, Error> {
+    if !path.is_file() {
+        return Err(Error::InvalidDatabase(path.to_path_buf()));
+    }
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut advisories: HashMap<String, Vec<Advisory>> = HashMap::new();
+    let packages = match json.as_object() {
+        Some(o) => o,
+        None => return Ok(advisories),
+    };
+    for (package, entries) in packages.iter() {
+        for entry in entries.as_array().into_iter().flatten() {
+            let range = match entry["vulnerable_versions"].as_str() {
+                Some(r) => r,
+                None => continue,
+            };
+            let id = match &entry["id"] {
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::String(s) => s.clone(),
+                _ => continue,
+            };
+            let patched = entry["patched_versions"]
+                .as_str()
+                .map(|p| vec![p.to_string()])
+                .unwrap_or_default();
+            advisories
+                .entry(package.clone())
+                .or_default()
+                .push(Advisory {
+                    id,
+                    title: entry["title"].as_str().map(String::from),
+                    url: entry["url"].as_str().map(String::from),
+                    severity: entry["severity"].as_str().map(String::from),
+                    informational: None,
+                    patched,
+                    affected: Affected::Within(npm_ranges(range)),
+                });
+        }
+    }
+    Ok(advisories)
+}
+
+fn npm_ranges(range: &str) -> Vec<VersionReq> {
+    range
+        .split("||")
+        .filter_map(|alternative| {
+            let alternative = alternative.trim();
+            if alternative.is_empty() || alternative == "*" {
+                return Some(VersionReq::STAR);
+            }
+            let comparators = match alternative.split_once(" - ") {
+                Some((from, to)) => vec![format!(">={}", from.trim()), format!("<={}", to.trim())],
+                None => {
+                    let mut comparators: Vec<String> = Vec::new();
+                    let mut operator = String::new();
+                    for token in alternative.split_whitespace() {
+                        let token = operator.clone() + token;
+                        let split = token.find(|c| !"<>=~^".contains(c)).unwrap_or(token.len());
+                        let (prefix, version) = token.split_at(split);
+                        if version.is_empty() {
+                            operator = prefix.to_string();
+                            continue;
+                        }
+                        operator.clear();
+                        let prefix = if prefix.is_empty() { "=" } else { prefix };
+                        comparators.push(format!("{}{}", prefix, version.trim_start_matches('v')));
+                    }
+                    comparators
+                }
+            };
+            VersionReq::parse(&comparators.join(", "))
+                .map_err(|e| log::warn!("[npm_ranges] {}: {:?}", range, e))
+                .ok()
+        })
+        .collect()
+}
+
+fn registry_caches() -> Vec<PathBuf> {
+    let index = match lockfile::cargo_home() {
+        Some(h) => h.join("registry").join("index"),
+        None => return Vec::new(),
+    };
+    match fs::read_dir(index) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path().join(".cache"))
+            .filter(|p| p.is_dir())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn index_path(name: &str) -> PathBuf {
+    match name.len() {
+        1 => Path::new("1").join(name),
+        2 => Path::new("2").join(name),
+        3 => Path::new("3").join(&name[..1]).join(name),
+        _ => Path::new(&name[..2]).join(&name[2..4]).join(name),
+    }
+}
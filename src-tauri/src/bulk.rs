@@ -0,0 +1,301 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use uuid::Uuid;
+
+use crate::{
+    git::{maintenance, remote, sync},
+    project::Project,
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum BulkAction {
+    Fetch,
+    FastForwardPull,
+    PruneRemoteBranches,
+    Gc,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BulkStatus {
+    Done,
+    DryRun,
+    Skipped,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectResult {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub status: BulkStatus,
+    pub messages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BulkSummary {
+    pub total: usize,
+    pub done: usize,
+    pub dry_run: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub results: Vec<ProjectResult>,
+}
+
+impl BulkSummary {
+    fn add(&mut self, result: ProjectResult) {
+        match result.status {
+            BulkStatus::Done => self.done += 1,
+            BulkStatus::DryRun => self.dry_run += 1,
+            BulkStatus::Skipped => self.skipped += 1,
+            BulkStatus::Failed => self.failed += 1,
+            BulkStatus::Cancelled => self.cancelled += 1,
+        }
+        self.results.push(result);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    RemoteError(#[from] remote::Error),
+    #[error(transparent)]
+    SyncError(#[from] sync::Error),
+    #[error(transparent)]
+    MaintenanceError(#[from] maintenance::Error),
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+pub fn run<F>(
+    action: BulkAction,
+    targets: Vec<(Uuid, Project)>,
+    token: Option<String>,
+    concurrency: usize,
+    dry_run: bool,
+    cancel: Arc<AtomicBool>,
+    progress: F,
+) -> BulkSummary
+where
+    F: Fn(&ProjectResult) + Send + Sync + 'static,
+{
+    let total = targets.len();
+    let queue = Arc::new(Mutex::new(targets.into_iter().collect::<VecDeque<_>>()));
+    let summary = Arc::new(Mutex::new(BulkSummary {
+        total,
+        ..Default::default()
+    }));
+    let progress = Arc::new(progress);
+
+    let workers = (0..concurrency.max(1).min(total.max(1)))
+        .map(|_| {
+            let queue = queue.clone();
+            let summary = summary.clone();
+            let progress = progress.clone();
+            let cancel = cancel.clone();
+            let action = action.clone();
+            let token = token.clone();
+            thread::spawn(move || loop {
+                let next = queue.lock().unwrap().pop_front();
+                let (id, project) = match next {
+                    Some(t) => t,
+                    None => break,
+                };
+                let result = if cancel.load(Ordering::SeqCst) {
+                    ProjectResult {
+                        id,
+                        name: project.local_name(),
+                        status: BulkStatus::Cancelled,
+                        messages: Vec::new(),
+                    }
+                } else {
+                    run_one(&action, id, &project, token.clone(), dry_run, &cancel)
+                };
+                progress(&result);
+                summary.lock().unwrap().add(result);
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        if let Err(e) = worker.join() {
+            log::error!("[bulk::run] worker panicked: {:?}", e);
+        }
+    }
+
+    let summary = summary.lock().unwrap().clone();
+    summary
+}
+
+fn run_one(
+    action: &BulkAction,
+    id: Uuid,
+    project: &Project,
+    token: Option<String>,
+    dry_run: bool,
+    cancel: &AtomicBool,
+) -> ProjectResult {
+    let name = project.local_name();
+    let repository = match project.open_repository() {
+        Ok(r) => r,
+        Err(e) => {
+            return ProjectResult {
+                id,
+                name,
+                status: BulkStatus::Skipped,
+                messages: vec![e.to_string()],
+            }
+        }
+    };
+
+    let outcome = match action {
+        BulkAction::Fetch => fetch(&repository, token, dry_run, cancel),
+        BulkAction::FastForwardPull => fast_forward(&repository, token, dry_run, cancel),
+        BulkAction::PruneRemoteBranches => prune(&repository, token, dry_run, cancel),
+        BulkAction::Gc => gc(&repository, dry_run, cancel),
+    };
+    match outcome {
+        Ok(messages) => ProjectResult {
+            id,
+            name,
+            status: if dry_run {
+                BulkStatus::DryRun
+            } else {
+                BulkStatus::Done
+            },
+            messages,
+        },
+        Err(e) if cancel.load(Ordering::SeqCst) => ProjectResult {
+            id,
+            name,
+            status: BulkStatus::Cancelled,
+            messages: vec![e.to_string()],
+        },
+        Err(e) => {
+            log::warn!("[bulk::run_one] {:?}", e);
+            ProjectResult {
+                id,
+                name,
+                status: BulkStatus::Failed,
+                messages: vec![e.to_string()],
+            }
+        }
+    }
+}
+
+fn fetch(
+    repository: &git2::Repository,
+    token: Option<String>,
+    dry_run: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<String>, Error> {
+    let names = remote::remote_names(repository)?;
+    if dry_run {
+        return Ok(names.iter().map(|n| format!("Would fetch {}", n)).collect());
+    }
+    let mut messages = Vec::new();
+    for name in names {
+        check_cancel(cancel)?;
+        remote::fetch_cancellable(repository, &name, token.clone(), cancel)?;
+        messages.push(format!("Fetched {}", name));
+    }
+    Ok(messages)
+}
+
+fn fast_forward(
+    repository: &git2::Repository,
+    token: Option<String>,
+    dry_run: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<String>, Error> {
+    check_cancel(cancel)?;
+    if !dry_run {
+        let result = sync::pull(repository, None, &sync::SyncOptions::default(), token)?;
+        let message = match result.outcome {
+            sync::SyncOutcome::FastForwarded => format!(
+                "Fast-forwarded {} by {} commits",
+                result.branch, result.behind
+            ),
+            _ => format!("{} is up to date", result.branch),
+        };
+        return Ok(vec![message]);
+    }
+
+    let branch = remote::current_branch(repository)?;
+    if sync::is_dirty(repository)? {
+        return Ok(vec![format!(
+            "Would refuse {}: uncommitted changes",
+            branch
+        )]);
+    }
+    let local = repository.find_branch(&branch, git2::BranchType::Local)?;
+    let upstream = match local.upstream() {
+        Ok(u) => u,
+        Err(_) => return Ok(vec![format!("Would refuse {}: no upstream", branch)]),
+    };
+    let (ahead, behind) = match (local.get().target(), upstream.get().target()) {
+        (Some(l), Some(u)) => repository.graph_ahead_behind(l, u)?,
+        _ => return Ok(vec![format!("Would refuse {}: no upstream", branch)]),
+    };
+    let message = if behind == 0 {
+        format!("{} is up to date as of the last fetch", branch)
+    } else if ahead == 0 {
+        format!("Would fast-forward {} by {} commits", branch, behind)
+    } else {
+        format!("Would refuse {}: diverged from upstream", branch)
+    };
+    Ok(vec![message])
+}
+
+fn prune(
+    repository: &git2::Repository,
+    token: Option<String>,
+    dry_run: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<String>, Error> {
+    let mut messages = Vec::new();
+    for name in remote::remote_names(repository)? {
+        check_cancel(cancel)?;
+        let refs = if dry_run {
+            remote::stale_tracking_refs(repository, &name, token.clone())?
+        } else {
+            remote::prune(repository, &name, token.clone())?
+        };
+        let verb = if dry_run { "Would prune" } else { "Pruned" };
+        messages.extend(refs.iter().map(|r| format!("{} {}", verb, r)));
+    }
+    Ok(messages)
+}
+
+fn gc(
+    repository: &git2::Repository,
+    dry_run: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<String>, Error> {
+    let loose = maintenance::object_stats(repository.path())?.loose_objects;
+    if dry_run {
+        return Ok(vec![format!("Would pack {} loose objects", loose)]);
+    }
+    let result = maintenance::gc(repository, cancel)?;
+    Ok(vec![format!(
+        "Packed {} of {} loose objects",
+        result.removed_loose, loose
+    )])
+}
+
+fn check_cancel(cancel: &AtomicBool) -> Result<(), Error> {
+    if cancel.load(Ordering::SeqCst) {
+        return Err(Error::Cancelled);
+    }
+    Ok(())
+}
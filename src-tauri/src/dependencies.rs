@@ -0,0 +1,354 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    manifest::{read_json, read_toml},
+    project::Project,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum DependencySource {
+    Path(PathBuf),
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub manifest: String,
+    pub source: DependencySource,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DependencyNode {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub package: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DependencyEdge {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub dependency: Dependency,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RemoteDependency {
+    pub from: Uuid,
+    pub name: String,
+    pub manifest: String,
+    pub url: String,
+    pub reference: Option<String>,
+    pub project: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    pub remotes: Vec<RemoteDependency>,
+}
+
+const CARGO_MANIFESTS: [&str; 2] = ["Cargo.toml", "src-tauri/Cargo.toml"];
+const CARGO_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+const NPM_TABLES: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+pub fn local_dependencies(path: &Path) -> Vec<Dependency> {
+    let mut dependencies = Vec::new();
+    let mut manifests = Vec::new();
+    for manifest in CARGO_MANIFESTS {
+        if let Some(document) = read_toml(&path.join(manifest)) {
+            let members = document
+                .get("workspace")
+                .and_then(|w| w.get("members"))
+                .and_then(|m| m.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str()).collect::<Vec<&str>>())
+                .unwrap_or_default();
+            let dir = Path::new(manifest)
+                .parent()
+                .unwrap_or_else(|| Path::new(""));
+            manifests.push(PathBuf::from(manifest));
+            for member in expand_members(path, dir, &members) {
+                manifests.push(member.join("Cargo.toml"));
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    manifests.retain(|m| seen.insert(m.clone()));
+    for manifest in manifests.iter() {
+        if let Some(document) = read_toml(&path.join(manifest)) {
+            cargo_dependencies(path, manifest, &document, &mut dependencies);
+        }
+    }
+
+    if let Some(json) = read_json(&path.join("package.json")) {
+        let workspaces = match &json["workspaces"] {
+            serde_json::Value::Array(a) => a.clone(),
+            w => w["packages"].as_array().cloned().unwrap_or_default(),
+        };
+        let members = workspaces
+            .iter()
+            .filter_map(|w| w.as_str())
+            .collect::<Vec<&str>>();
+        npm_dependencies(path, Path::new("package.json"), &json, &mut dependencies);
+        for member in expand_members(path, Path::new(""), &members) {
+            let manifest = member.join("package.json");
+            if let Some(json) = read_json(&path.join(&manifest)) {
+                npm_dependencies(path, &manifest, &json, &mut dependencies);
+            }
+        }
+    }
+    dependencies
+}
+
+pub fn build(projects: &[(Uuid, Project)]) -> DependencyGraph {
+    let locals = projects
+        .iter()
+        .filter_map(|(id, prj)| {
+            let path = prj.local_path().ok()?;
+            Some((*id, fs::canonicalize(&path).unwrap_or(path)))
+        })
+        .collect::<Vec<(Uuid, PathBuf)>>();
+    let urls = projects
+        .iter()
+        .map(|(id, prj)| (*id, project_urls(prj)))
+        .collect::<Vec<(Uuid, Vec<String>)>>();
+
+    let mut graph = DependencyGraph::default();
+    for (id, path) in locals.iter() {
+        let project = match projects.iter().find(|(i, _)| i == id) {
+            Some((_, p)) => p,
+            None => continue,
+        };
+        graph.nodes.push(DependencyNode {
+            id: *id,
+            name: project.local_name(),
+            package: project.manifest().and_then(|m| m.name),
+        });
+
+        for dependency in local_dependencies(path) {
+            let target = match &dependency.source {
+                DependencySource::Path(p) => {
+                    let p = fs::canonicalize(p).unwrap_or_else(|_| p.clone());
+                    locals
+                        .iter()
+                        .filter(|(_, l)| p.starts_with(l))
+                        .max_by_key(|(_, l)| l.components().count())
+                        .map(|(i, _)| *i)
+                }
+                DependencySource::Git { url, reference } => {
+                    let normalized = normalize_url(url);
+                    let target = urls
+                        .iter()
+                        .find(|(_, us)| us.contains(&normalized))
+                        .map(|(i, _)| *i);
+                    graph.remotes.push(RemoteDependency {
+                        from: *id,
+                        name: dependency.name.clone(),
+                        manifest: dependency.manifest.clone(),
+                        url: url.clone(),
+                        reference: reference.clone(),
+                        project: target,
+                    });
+                    target
+                }
+            };
+            match target {
+                Some(to) if to != *id => graph.edges.push(DependencyEdge {
+                    from: *id,
+                    to,
+                    dependency,
+                }),
+                _ => (),
+            }
+        }
+    }
+    graph.nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    graph
+}
+
+pub fn dependents(graph: &DependencyGraph, id: &Uuid) -> Vec<Uuid> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    let mut pending = VecDeque::from(vec![*id]);
+    while let Some(current) = pending.pop_front() {
+        for edge in graph.edges.iter().filter(|e| e.to == current) {
+            if edge.from != *id && seen.insert(edge.from) {
+                result.push(edge.from);
+                pending.push_back(edge.from);
+            }
+        }
+    }
+    result
+}
+
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim().trim_start_matches("git+");
+    let url = match url.split_once(':') {
+        Some(("github", rest)) => format!("github.com/{}", rest),
+        Some(("gitlab", rest)) => format!("gitlab.com/{}", rest),
+        Some(("bitbucket", rest)) => format!("bitbucket.org/{}", rest),
+        _ => match url.split_once("://") {
+            Some((_, rest)) => rest.to_string(),
+            None => url.replacen(':', "/", 1),
+        },
+    };
+    let url = match url.split_once('@') {
+        Some((user, rest)) if !user.contains('/') => rest.to_string(),
+        _ => url,
+    };
+    let url = url.split('#').next().unwrap_or_default();
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+fn project_urls(project: &Project) -> Vec<String> {
+    let mut urls = project
+        .remotes()
+        .unwrap_or_default()
+        .iter()
+        .map(|r| r.url.clone())
+        .collect::<Vec<String>>();
+    if let Some(remote) = &project.remote {
+        urls.extend(remote.url.clone());
+        urls.extend(remote.ssh_url.clone());
+    }
+    urls.iter().map(|u| normalize_url(u)).collect()
+}
+
+fn cargo_dependencies(
+    root: &Path,
+    manifest: &Path,
+    document: &toml_edit::Document,
+    dependencies: &mut Vec<Dependency>,
+) {
+    let mut tables = CARGO_TABLES
+        .iter()
+        .filter_map(|t| document.get(t))
+        .collect::<Vec<&toml_edit::Item>>();
+    if let Some(workspace) = document
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+    {
+        tables.push(workspace);
+    }
+    if let Some(targets) = document.get("target").and_then(|t| t.as_table_like()) {
+        for (_, target) in targets.iter() {
+            tables.extend(CARGO_TABLES.iter().filter_map(|t| target.get(t)));
+        }
+    }
+
+    let dir = root.join(manifest.parent().unwrap_or_else(|| Path::new("")));
+    for table in tables.iter().filter_map(|t| t.as_table_like()) {
+        for (name, value) in table.iter() {
+            let name = value
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(name)
+                .to_string();
+            let source = if let Some(path) = value.get("path").and_then(|p| p.as_str()) {
+                DependencySource::Path(dir.join(path))
+            } else if let Some(url) = value.get("git").and_then(|g| g.as_str()) {
+                DependencySource::Git {
+                    url: url.to_string(),
+                    reference: ["rev", "tag", "branch"]
+                        .iter()
+                        .find_map(|r| value.get(r).and_then(|v| v.as_str()))
+                        .map(String::from),
+                }
+            } else {
+                continue;
+            };
+            dependencies.push(Dependency {
+                name,
+                manifest: manifest.to_string_lossy().replace('\\', "/"),
+                source,
+            });
+        }
+    }
+}
+
+fn npm_dependencies(
+    root: &Path,
+    manifest: &Path,
+    json: &serde_json::Value,
+    dependencies: &mut Vec<Dependency>,
+) {
+    let dir = root.join(manifest.parent().unwrap_or_else(|| Path::new("")));
+    for table in NPM_TABLES {
+        let entries = match json[table].as_object() {
+            Some(o) => o,
+            None => continue,
+        };
+        for (name, value) in entries.iter() {
+            let spec = match value.as_str() {
+                Some(s) => s,
+                None => continue,
+            };
+            let source = if let Some(path) = ["file:", "link:", "portal:"]
+                .iter()
+                .find_map(|p| spec.strip_prefix(p))
+            {
+                DependencySource::Path(dir.join(path))
+            } else if is_git_spec(spec) {
+                let (url, reference) = match spec.split_once('#') {
+                    Some((u, r)) => (u, Some(r.to_string())),
+                    None => (spec, None),
+                };
+                DependencySource::Git {
+                    url: url.to_string(),
+                    reference,
+                }
+            } else {
+                continue;
+            };
+            dependencies.push(Dependency {
+                name: name.clone(),
+                manifest: manifest.to_string_lossy().replace('\\', "/"),
+                source,
+            });
+        }
+    }
+}
+
+fn is_git_spec(spec: &str) -> bool {
+    ["git+", "git://", "git@", "github:", "gitlab:", "bitbucket:"]
+        .iter()
+        .any(|p| spec.starts_with(p))
+        || (spec.contains("://") && spec.split('#').next().unwrap_or_default().ends_with(".git"))
+}
+
+fn expand_members(root: &Path, dir: &Path, members: &[&str]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for member in members {
+        let pattern = root.join(dir).join(member);
+        let entries = match glob::glob(&pattern.to_string_lossy()) {
+            Ok(es) => es,
+            Err(e) => {
+                log::warn!("{:?}: {:?}", pattern, e);
+                continue;
+            }
+        };
+        for path in entries.flatten().filter(|p| p.is_dir()) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                paths.push(relative.to_path_buf());
+            }
+        }
+    }
+    paths
+}
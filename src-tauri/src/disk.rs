@@ -0,0 +1,281 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{offset::Utc, DateTime, TimeZone};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ArtifactKind {
+    CargoTarget,
+    NodeModules,
+    PythonVenv,
+    PythonCache,
+    GradleCache,
+    BuildOutput,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Artifact {
+    pub path: String,
+    pub kind: ArtifactKind,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub tracked: u64,
+    pub git: u64,
+    pub artifacts: u64,
+    pub artifact_dirs: Vec<Artifact>,
+    pub last_activity: Option<DateTime<Utc>>,
+    pub uncommitted: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArtifactRemoval {
+    pub path: String,
+    pub kind: ArtifactKind,
+    pub bytes: u64,
+    pub removed: bool,
+    pub error: Option<String>,
+}
+
+const MAX_DEPTH: usize = 4;
+
+pub fn disk_usage(path: &Path) -> Result<DiskUsage, Error> {
+    let repository = git2::Repository::open(path).ok();
+    let artifact_dirs = find_artifacts(path, repository.as_ref());
+    let tracked = match &repository {
+        Some(r) => tracked_size(r)?,
+        None => 0,
+    };
+    let git = dir_size(&path.join(".git"));
+    let changed = match &repository {
+        Some(r) => changed_paths(r)?,
+        None => Vec::new(),
+    };
+
+    Ok(DiskUsage {
+        total: dir_size(path),
+        tracked,
+        git,
+        artifacts: artifact_dirs.iter().map(|a| a.bytes).sum(),
+        last_activity: last_activity(path, repository.as_ref(), &changed, &artifact_dirs),
+        uncommitted: !changed.is_empty(),
+        artifact_dirs,
+    })
+}
+
+pub fn is_inactive(usage: &DiskUsage, inactive_days: i64) -> bool {
+    let cutoff = Utc::now() - chrono::Duration::days(inactive_days);
+    !usage.uncommitted && usage.last_activity.map(|d| d < cutoff).unwrap_or(false)
+}
+
+pub fn remove_artifacts(
+    path: &Path,
+    usage: &DiskUsage,
+    kinds: &[ArtifactKind],
+    dry_run: bool,
+) -> Vec<ArtifactRemoval> {
+    usage
+        .artifact_dirs
+        .iter()
+        .filter(|a| kinds.contains(&a.kind))
+        .map(|artifact| {
+            let mut removal = ArtifactRemoval {
+                path: artifact.path.clone(),
+                kind: artifact.kind,
+                bytes: artifact.bytes,
+                removed: false,
+                error: None,
+            };
+            if dry_run {
+                return removal;
+            }
+            match fs::remove_dir_all(path.join(&artifact.path)) {
+                Ok(()) => {
+                    log::info!("[remove_artifacts] removed {:?}", path.join(&artifact.path));
+                    removal.removed = true;
+                }
+                Err(e) => {
+                    log::warn!("[remove_artifacts] {:?}", e);
+                    removal.error = Some(e.to_string());
+                }
+            }
+            removal
+        })
+        .collect()
+}
+
+fn find_artifacts(root: &Path, repository: Option<&git2::Repository>) -> Vec<Artifact> {
+    let mut artifacts = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(es) => es,
+            Err(e) => {
+                log::warn!("{:?}: {:?}", dir, e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            match artifact_kind(&dir, &name) {
+                Some(kind) if is_artifact(repository, &relative, kind) => {
+                    artifacts.push(Artifact {
+                        path: relative.to_string_lossy().replace('\\', "/"),
+                        kind,
+                        bytes: dir_size(&path),
+                    });
+                }
+                _ if depth + 1 < MAX_DEPTH && !is_ignored(repository, &relative) => {
+                    pending.push((path, depth + 1));
+                }
+                _ => (),
+            }
+        }
+    }
+    artifacts.sort_by_key(|a| Reverse(a.bytes));
+    artifacts
+}
+
+fn artifact_kind(parent: &Path, name: &str) -> Option<ArtifactKind> {
+    match name {
+        "target" if parent.join("Cargo.toml").is_file() => Some(ArtifactKind::CargoTarget),
+        "node_modules" => Some(ArtifactKind::NodeModules),
+        ".venv" | "venv" if parent.join(name).join("pyvenv.cfg").is_file() => {
+            Some(ArtifactKind::PythonVenv)
+        }
+        "__pycache__" | ".pytest_cache" | ".mypy_cache" | ".ruff_cache" | ".tox" => {
+            Some(ArtifactKind::PythonCache)
+        }
+        ".gradle" => Some(ArtifactKind::GradleCache),
+        "dist" | "build" | ".next" | ".nuxt" | ".svelte-kit" | ".turbo" => {
+            Some(ArtifactKind::BuildOutput)
+        }
+        _ => None,
+    }
+}
+
+fn is_artifact(repository: Option<&git2::Repository>, relative: &Path, kind: ArtifactKind) -> bool {
+    match repository {
+        Some(_) => is_ignored(repository, relative),
+        None => kind != ArtifactKind::BuildOutput,
+    }
+}
+
+fn is_ignored(repository: Option<&git2::Repository>, relative: &Path) -> bool {
+    repository
+        .and_then(|r| r.is_path_ignored(relative).ok())
+        .unwrap_or(false)
+}
+
+fn tracked_size(repository: &git2::Repository) -> Result<u64, Error> {
+    let index = repository.index()?;
+    Ok(index.iter().map(|e| e.file_size as u64).sum())
+}
+
+fn changed_paths(repository: &git2::Repository) -> Result<Vec<PathBuf>, Error> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repository.statuses(Some(&mut options))?;
+    Ok(statuses
+        .iter()
+        .filter_map(|s| s.path().map(PathBuf::from))
+        .collect())
+}
+
+fn last_activity(
+    path: &Path,
+    repository: Option<&git2::Repository>,
+    changed: &[PathBuf],
+    artifacts: &[Artifact],
+) -> Option<DateTime<Utc>> {
+    match repository {
+        Some(repository) => {
+            let commit = repository
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .and_then(|c| Utc.timestamp_opt(c.time().seconds(), 0).single());
+            let changed = changed
+                .iter()
+                .filter_map(|p| modified_at(&path.join(p)))
+                .max();
+            commit.max(changed)
+        }
+        None => {
+            let skip = artifacts
+                .iter()
+                .map(|a| path.join(&a.path))
+                .collect::<Vec<PathBuf>>();
+            latest_modified(path, &skip)
+        }
+    }
+}
+
+fn modified_at(path: &Path) -> Option<DateTime<Utc>> {
+    let modified = fs::symlink_metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified))
+}
+
+fn latest_modified(path: &Path, skip: &[PathBuf]) -> Option<DateTime<Utc>> {
+    let mut latest = None;
+    let mut pending: Vec<PathBuf> = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(es) => es,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            if is_dir && !skip.contains(&path) {
+                pending.push(path);
+            } else if !is_dir {
+                latest = latest.max(modified_at(&path));
+            }
+        }
+    }
+    latest
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending: Vec<PathBuf> = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(es) => es,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    total
+}
@@ -0,0 +1,123 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("Checking out {0} would overwrite local changes")]
+    WouldClobber(String),
+    #[error("Branch {0} is not merged")]
+    NotMerged(String),
+    #[error("Branch {0} is checked out")]
+    CheckedOut(String),
+}
+
+pub fn create_branch(
+    repository: &git2::Repository,
+    name: &str,
+    from: &str,
+) -> Result<String, Error> {
+    let commit = repository.revparse_single(from)?.peel_to_commit()?;
+    repository.branch(name, &commit, false)?;
+    log::info!("[create_branch] {} from {}", name, from);
+    Ok(commit.id().to_string())
+}
+
+pub fn checkout_branch(repository: &git2::Repository, name: &str) -> Result<(), Error> {
+    let (branch, created) = match repository.find_branch(name, git2::BranchType::Local) {
+        Ok(b) => (b, false),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            (track_remote_branch(repository, name)?, true)
+        }
+        Err(e) => return Err(Error::GitError(e)),
+    };
+    let result = checkout_reference(repository, branch.get(), name);
+    if result.is_err() && created {
+        if let Err(e) = branch.into_reference().delete() {
+            log::warn!("[checkout_branch] {:?}", e);
+        }
+    }
+    result?;
+    log::info!("[checkout_branch] {}", name);
+    Ok(())
+}
+
+fn checkout_reference(
+    repository: &git2::Repository,
+    reference: &git2::Reference,
+    name: &str,
+) -> Result<(), Error> {
+    let refname = reference.name().unwrap_or_default().to_string();
+    let target = reference.peel(git2::ObjectType::Commit)?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    match repository.checkout_tree(&target, Some(&mut checkout)) {
+        Ok(()) => {}
+        Err(e) if e.code() == git2::ErrorCode::Conflict => {
+            return Err(Error::WouldClobber(name.to_string()))
+        }
+        Err(e) => return Err(Error::GitError(e)),
+    }
+    repository.set_head(&refname)?;
+    Ok(())
+}
+
+pub fn rename_branch(
+    repository: &git2::Repository,
+    name: &str,
+    new_name: &str,
+) -> Result<(), Error> {
+    let mut branch = repository.find_branch(name, git2::BranchType::Local)?;
+    branch.rename(new_name, false)?;
+    log::info!("[rename_branch] {} to {}", name, new_name);
+    Ok(())
+}
+
+pub fn delete_branch(repository: &git2::Repository, name: &str, force: bool) -> Result<(), Error> {
+    let mut branch = repository.find_branch(name, git2::BranchType::Local)?;
+    if branch.is_head() {
+        return Err(Error::CheckedOut(name.to_string()));
+    }
+    if !force && !is_merged(repository, &branch)? {
+        return Err(Error::NotMerged(name.to_string()));
+    }
+    branch.delete()?;
+    log::info!("[delete_branch] {}", name);
+    Ok(())
+}
+
+pub fn is_merged(repository: &git2::Repository, branch: &git2::Branch) -> Result<bool, Error> {
+    let tip = match branch.get().target() {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+    let mut bases = Vec::new();
+    if let Some(head) = repository.head().ok().and_then(|h| h.target()) {
+        bases.push(head);
+    }
+    if let Some(upstream) = branch.upstream().ok().and_then(|u| u.get().target()) {
+        bases.push(upstream);
+    }
+    for base in bases {
+        if base == tip || repository.graph_descendant_of(base, tip)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn track_remote_branch<'a>(
+    repository: &'a git2::Repository,
+    name: &str,
+) -> Result<git2::Branch<'a>, Error> {
+    let remote_name = format!("origin/{}", name);
+    let remote = repository.find_branch(&remote_name, git2::BranchType::Remote)?;
+    let commit = remote.get().peel_to_commit()?;
+    let mut branch = repository.branch(name, &commit, false)?;
+    if let Err(e) = branch.set_upstream(Some(&remote_name)) {
+        if let Err(e) = branch.delete() {
+            log::warn!("[track_remote_branch] {:?}", e);
+        }
+        return Err(Error::GitError(e));
+    }
+    Ok(branch)
+}
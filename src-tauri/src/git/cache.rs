@@ -0,0 +1,67 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Entry<T> {
+    key: String,
+    value: T,
+}
+
+pub fn head_key(repository: &git2::Repository) -> Option<String> {
+    let head = repository.head().ok()?;
+    head.target().map(|oid| oid.to_string())
+}
+
+fn repository_dir(dir: &Path, repository: &git2::Repository) -> Option<PathBuf> {
+    let path = repository.path().to_string_lossy();
+    let oid = git2::Oid::hash_object(git2::ObjectType::Blob, path.as_bytes()).ok()?;
+    Some(dir.join(oid.to_string()))
+}
+
+pub fn read<T>(dir: &Path, repository: &git2::Repository, name: &str, key: &str) -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    let path = repository_dir(dir, repository)?.join(name);
+    let content = fs::read_to_string(path).ok()?;
+    let entry = serde_json::from_str::<Entry<T>>(&content).ok()?;
+    if entry.key != key {
+        return None;
+    }
+    Some(entry.value)
+}
+
+pub fn write<T>(
+    dir: &Path,
+    repository: &git2::Repository,
+    name: &str,
+    key: &str,
+    value: &T,
+) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    let dir = match repository_dir(dir, repository) {
+        Some(d) => d,
+        None => return Ok(()),
+    };
+    fs::create_dir_all(&dir)?;
+    let entry = Entry {
+        key: key.to_string(),
+        value,
+    };
+    fs::write(dir.join(name), serde_json::to_string(&entry)?)?;
+    Ok(())
+}
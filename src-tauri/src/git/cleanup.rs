@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use chrono::{offset::Utc, DateTime, TimeZone};
+
+use super::branch;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    BranchError(#[from] branch::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum StaleReason {
+    Merged,
+    Gone,
+    Inactive,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StaleBranch {
+    pub name: String,
+    pub commit: String,
+    pub last_commit: Option<DateTime<Utc>>,
+    pub reasons: Vec<StaleReason>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CleanupReport {
+    pub default_branch: Option<String>,
+    pub branches: Vec<StaleBranch>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BranchDeletion {
+    pub name: String,
+    pub deleted: bool,
+    pub error: Option<String>,
+}
+
+pub fn default_branch(repository: &git2::Repository) -> Option<String> {
+    if let Ok(head) = repository.find_reference("refs/remotes/origin/HEAD") {
+        if let Some(target) = head.symbolic_target() {
+            if let Some(name) = target.strip_prefix("refs/remotes/origin/") {
+                return Some(name.to_string());
+            }
+        }
+    }
+    ["main", "master", "trunk", "develop"]
+        .iter()
+        .find(|name| {
+            repository
+                .find_branch(name, git2::BranchType::Local)
+                .is_ok()
+        })
+        .map(|name| name.to_string())
+}
+
+pub fn cleanup_report(
+    repository: &git2::Repository,
+    branch_commit: &HashMap<String, String>,
+    inactive_days: i64,
+) -> Result<CleanupReport, Error> {
+    let default_branch = default_branch(repository);
+    let default_tip = default_branch.as_ref().and_then(|name| {
+        repository
+            .refname_to_id(&format!("refs/remotes/origin/{}", name))
+            .or_else(|_| repository.refname_to_id(&format!("refs/heads/{}", name)))
+            .ok()
+    });
+    let head = repository
+        .head()
+        .ok()
+        .and_then(|h| h.shorthand().map(|s| s.to_string()));
+    let cutoff = Utc::now() - chrono::Duration::days(inactive_days);
+
+    let mut branches = Vec::new();
+    for (name, sha) in branch_commit.iter() {
+        if Some(name) == default_branch.as_ref() || Some(name) == head.as_ref() {
+            continue;
+        }
+        let oid = match git2::Oid::from_str(sha) {
+            Ok(o) => o,
+            Err(e) => {
+                log::warn!("{:?}", e);
+                continue;
+            }
+        };
+        let last_commit = repository
+            .find_commit(oid)
+            .ok()
+            .and_then(|c| Utc.timestamp_opt(c.time().seconds(), 0).single());
+
+        let mut reasons = Vec::new();
+        if let Some(tip) = default_tip {
+            if tip == oid || repository.graph_descendant_of(tip, oid)? {
+                reasons.push(StaleReason::Merged);
+            }
+        }
+        if is_gone(repository, name) {
+            reasons.push(StaleReason::Gone);
+        }
+        if last_commit.map(|t| t < cutoff).unwrap_or(false) {
+            reasons.push(StaleReason::Inactive);
+        }
+        if reasons.is_empty() {
+            continue;
+        }
+
+        branches.push(StaleBranch {
+            name: name.clone(),
+            commit: sha.clone(),
+            last_commit,
+            reasons,
+        });
+    }
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(CleanupReport {
+        default_branch,
+        branches,
+    })
+}
+
+pub fn delete_branches(repository: &git2::Repository, names: &[String]) -> Vec<BranchDeletion> {
+    names
+        .iter()
+        .map(|name| match branch::delete_branch(repository, name, true) {
+            Ok(()) => BranchDeletion {
+                name: name.clone(),
+                deleted: true,
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("{:?}", e);
+                BranchDeletion {
+                    name: name.clone(),
+                    deleted: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        })
+        .collect()
+}
+
+fn is_gone(repository: &git2::Repository, branch: &str) -> bool {
+    let refname = format!("refs/heads/{}", branch);
+    let upstream = match repository.branch_upstream_name(&refname) {
+        Ok(u) => u,
+        Err(_) => return false,
+    };
+    match upstream.as_str() {
+        Some(name) => repository.find_reference(name).is_err(),
+        None => false,
+    }
+}
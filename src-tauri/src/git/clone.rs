@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use super::credentials;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("Destination already exists")]
+    DestinationExists,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TransferProgress {
+    pub total_objects: usize,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub received_bytes: usize,
+}
+
+pub fn clone_repository<F>(
+    url: &str,
+    destination: &Path,
+    token: Option<String>,
+    mut progress: F,
+) -> Result<git2::Repository, Error>
+where
+    F: FnMut(TransferProgress),
+{
+    if destination.exists() {
+        return Err(Error::DestinationExists);
+    }
+
+    let mut last_received = None;
+    let mut last_indexed = None;
+    let mut callbacks = credentials::remote_callbacks(token);
+    callbacks.transfer_progress(|p| {
+        let received = Some(p.received_objects());
+        let indexed = Some(p.indexed_objects());
+        if received != last_received || indexed != last_indexed {
+            last_received = received;
+            last_indexed = indexed;
+            progress(TransferProgress {
+                total_objects: p.total_objects(),
+                received_objects: p.received_objects(),
+                indexed_objects: p.indexed_objects(),
+                received_bytes: p.received_bytes(),
+            });
+        }
+        true
+    });
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    let repository = git2::build::RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, destination)?;
+    log::info!("[clone_repository] cloned {} into {:?}", url, destination);
+
+    Ok(repository)
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Commit message is empty")]
+    EmptyMessage,
+    #[error("Nothing staged to commit")]
+    NothingToCommit,
+    #[error("Signing failed: {0}")]
+    SigningFailed(String),
+    #[error("Cannot commit while a {0:?} is in progress")]
+    InProgress(git2::RepositoryState),
+}
+
+pub fn commit(repository: &git2::Repository, message: &str) -> Result<git2::Oid, Error> {
+    if message.trim().is_empty() {
+        return Err(Error::EmptyMessage);
+    }
+    let state = repository.state();
+    if !matches!(
+        state,
+        git2::RepositoryState::Clean | git2::RepositoryState::Merge
+    ) {
+        return Err(Error::InProgress(state));
+    }
+    let signature = repository.signature()?;
+    let mut index = repository.index()?;
+    index.read(false)?;
+    let tree = repository.find_tree(index.write_tree()?)?;
+    let mut parents = repository
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .into_iter()
+        .collect::<Vec<git2::Commit>>();
+    if state == git2::RepositoryState::Merge {
+        let merge_heads = fs::read_to_string(repository.path().join("MERGE_HEAD"))?;
+        for line in merge_heads.lines().filter(|l| !l.trim().is_empty()) {
+            parents.push(repository.find_commit(git2::Oid::from_str(line.trim())?)?);
+        }
+    }
+    if let [parent] = parents.as_slice() {
+        if parent.tree_id() == tree.id() {
+            return Err(Error::NothingToCommit);
+        }
+    }
+    let parents = parents.iter().collect::<Vec<&git2::Commit>>();
+
+    let config = repository.config()?;
+    if !config.get_bool("commit.gpgsign").unwrap_or(false) {
+        let oid = repository.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        repository.cleanup_state()?;
+        log::info!("[commit] {}", oid);
+        return Ok(oid);
+    }
+
+    let buffer =
+        repository.commit_create_buffer(&signature, &signature, message, &tree, &parents)?;
+    let content = buffer.as_str().ok_or(Error::SigningFailed(String::from(
+        "commit is not valid UTF-8",
+    )))?;
+    let signed = sign(&config, content)?;
+    let oid = repository.commit_signed(content, &signed, None)?;
+
+    let summary = message.lines().next().unwrap_or_default();
+    let reflog = format!("commit: {}", summary);
+    let head = repository.find_reference("HEAD")?;
+    match head.symbolic_target() {
+        Some(name) => {
+            repository.reference(name, oid, true, &reflog)?;
+        }
+        None => repository.set_head_detached(oid)?,
+    }
+    repository.cleanup_state()?;
+    log::info!("[commit] {} (signed)", oid);
+    Ok(oid)
+}
+
+fn sign(config: &git2::Config, content: &str) -> Result<String, Error> {
+    let format = config
+        .get_string("gpg.format")
+        .unwrap_or_else(|_| String::from("openpgp"));
+    let key = config.get_string("user.signingkey").ok();
+
+    let mut command = match format.as_str() {
+        "ssh" => {
+            let program = config
+                .get_string("gpg.ssh.program")
+                .unwrap_or_else(|_| String::from("ssh-keygen"));
+            let key = key.ok_or(Error::SigningFailed(String::from(
+                "user.signingkey is not set",
+            )))?;
+            let mut command = Command::new(program);
+            command.args(["-Y", "sign", "-n", "git", "-f", &key]);
+            command
+        }
+        _ => {
+            let program = config
+                .get_string("gpg.program")
+                .unwrap_or_else(|_| String::from("gpg"));
+            let mut command = Command::new(program);
+            command.args(["--status-fd=2", "-bsa"]);
+            if let Some(key) = key {
+                command.args(["-u", &key]);
+            }
+            command
+        }
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::SigningFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use git2::{Cred, CredentialType, RemoteCallbacks};
+
+const MAX_ATTEMPTS: usize = 3;
+
+pub fn remote_callbacks<'a>(token: Option<String>) -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0usize;
+    callbacks.credentials(move |_url, username, allowed| {
+        attempts += 1;
+        if attempts > MAX_ATTEMPTS {
+            return Err(git2::Error::from_str("Authentication failed"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            let username = username.unwrap_or("git");
+            if attempts == 1 {
+                return Cred::ssh_key_from_agent(username);
+            }
+            if let Some(key) = default_ssh_key() {
+                return Cred::ssh_key(username, None, &key, None);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(token) = &token {
+                return Cred::userpass_plaintext("x-access-token", token);
+            }
+        }
+        Cred::default()
+    });
+    callbacks
+}
+
+fn default_ssh_key() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    let ssh = PathBuf::from(home).join(".ssh");
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .map(|name| ssh.join(name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,184 @@
+pub const MAX_FILE_BYTES: i64 = 512 * 1024;
+pub const MAX_LINES_PER_FILE: usize = 5000;
+pub const MAX_TOTAL_LINES: usize = 50_000;
+pub const MAX_TOTAL_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum DiffTarget {
+    Unstaged,
+    Staged,
+    All,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum LineOrigin {
+    Context,
+    Addition,
+    Deletion,
+    NoNewlineContext,
+    NoNewlineAddition,
+    NoNewlineDeletion,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiffLine {
+    pub origin: LineOrigin,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FileDiff {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub status: String,
+    pub binary: bool,
+    pub truncated: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiffResult {
+    pub files: Vec<FileDiff>,
+    pub truncated: bool,
+}
+
+pub fn diff<'a>(
+    repository: &'a git2::Repository,
+    target: &DiffTarget,
+    path: Option<&str>,
+) -> Result<git2::Diff<'a>, Error> {
+    let mut options = git2::DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true)
+        .max_size(MAX_FILE_BYTES);
+    if let Some(path) = path {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+
+    let head = match repository.head() {
+        Ok(h) => Some(h.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let diff = match target {
+        DiffTarget::Unstaged => repository.diff_index_to_workdir(None, Some(&mut options))?,
+        DiffTarget::Staged => {
+            repository.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?
+        }
+        DiffTarget::All => {
+            repository.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut options))?
+        }
+    };
+    Ok(diff)
+}
+
+pub fn file_diffs(diff: &git2::Diff, max_lines: usize) -> Result<DiffResult, Error> {
+    let mut files = Vec::new();
+    let mut total_lines = 0;
+    let mut total_bytes = 0;
+    let mut truncated = false;
+    for (idx, delta) in diff.deltas().enumerate() {
+        let mut file = FileDiff {
+            old_path: path_string(delta.old_file().path()),
+            new_path: path_string(delta.new_file().path()),
+            status: format!("{:?}", delta.status()),
+            binary: delta.flags().is_binary(),
+            truncated: false,
+            hunks: Vec::new(),
+        };
+        if truncated {
+            file.truncated = true;
+            files.push(file);
+            continue;
+        }
+        let patch = match git2::Patch::from_diff(diff, idx)? {
+            Some(p) => p,
+            None => {
+                file.binary = true;
+                files.push(file);
+                continue;
+            }
+        };
+        if patch.delta().flags().is_binary() {
+            file.binary = true;
+            files.push(file);
+            continue;
+        }
+
+        let mut remaining = max_lines;
+        for h in 0..patch.num_hunks() {
+            let (hunk, num_lines) = patch.hunk(h)?;
+            let mut lines = Vec::new();
+            for l in 0..num_lines {
+                if remaining == 0 {
+                    file.truncated = true;
+                    break;
+                }
+                let line = patch.line_in_hunk(h, l)?;
+                if total_lines >= MAX_TOTAL_LINES
+                    || total_bytes + line.content().len() > MAX_TOTAL_BYTES
+                {
+                    file.truncated = true;
+                    truncated = true;
+                    break;
+                }
+                let origin = match line.origin_value() {
+                    git2::DiffLineType::Context => LineOrigin::Context,
+                    git2::DiffLineType::Addition => LineOrigin::Addition,
+                    git2::DiffLineType::Deletion => LineOrigin::Deletion,
+                    git2::DiffLineType::ContextEOFNL => LineOrigin::NoNewlineContext,
+                    git2::DiffLineType::AddEOFNL => LineOrigin::NoNewlineAddition,
+                    git2::DiffLineType::DeleteEOFNL => LineOrigin::NoNewlineDeletion,
+                    _ => continue,
+                };
+                lines.push(DiffLine {
+                    origin,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    content: String::from_utf8_lossy(line.content()).to_string(),
+                });
+                remaining -= 1;
+                total_lines += 1;
+                total_bytes += line.content().len();
+            }
+            file.hunks.push(DiffHunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+            if file.truncated {
+                break;
+            }
+        }
+        files.push(file);
+    }
+    Ok(DiffResult { files, truncated })
+}
+
+fn path_string(path: Option<&std::path::Path>) -> Option<String> {
+    path.and_then(|p| p.to_str()).map(|s| s.to_string())
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use super::history::{self, CommitInfo};
+
+pub const DEFAULT_LIMIT: usize = 500;
+pub const MAX_LIMIT: usize = 5000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    HistoryError(#[from] history::Error),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphNode {
+    pub row: usize,
+    pub column: usize,
+    pub commit: CommitInfo,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub from_row: usize,
+    pub from_column: usize,
+    pub to_row: Option<usize>,
+    pub to_column: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphLayout {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub columns: usize,
+    pub truncated: bool,
+}
+
+pub fn commit_graph(
+    repository: &git2::Repository,
+    branches: &[String],
+    limit: usize,
+) -> Result<GraphLayout, Error> {
+    let limit = limit.min(MAX_LIMIT);
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    if branches.is_empty() {
+        revwalk.push_head()?;
+    }
+    for branch in branches {
+        revwalk.push(repository.revparse_single(branch)?.peel_to_commit()?.id())?;
+    }
+
+    let refs = history::ref_names(repository)?;
+    let mut commits = Vec::new();
+    let mut truncated = false;
+    for oid in revwalk {
+        if commits.len() >= limit {
+            truncated = true;
+            break;
+        }
+        commits.push(repository.find_commit(oid?)?);
+    }
+
+    let mut lanes: Vec<Option<git2::Oid>> = Vec::new();
+    let mut positions: HashMap<git2::Oid, (usize, usize)> = HashMap::new();
+    let mut pending: Vec<(git2::Oid, usize, usize, git2::Oid, usize)> = Vec::new();
+    let mut nodes = Vec::new();
+    let mut columns = 0;
+
+    for (row, commit) in commits.iter().enumerate() {
+        let oid = commit.id();
+        let column = match lanes.iter().position(|l| *l == Some(oid)) {
+            Some(c) => c,
+            None => free_lane(&mut lanes),
+        };
+        for lane in lanes.iter_mut() {
+            if *lane == Some(oid) {
+                *lane = None;
+            }
+        }
+
+        for (i, parent) in commit.parent_ids().enumerate() {
+            let parent_column = match lanes.iter().position(|l| *l == Some(parent)) {
+                Some(c) => c,
+                None if i == 0 => {
+                    lanes[column] = Some(parent);
+                    column
+                }
+                None => {
+                    let c = free_lane(&mut lanes);
+                    lanes[c] = Some(parent);
+                    c
+                }
+            };
+            pending.push((oid, row, column, parent, parent_column));
+        }
+
+        positions.insert(oid, (row, column));
+        columns = columns.max(lanes.len()).max(column + 1);
+        nodes.push(GraphNode {
+            row,
+            column,
+            commit: history::commit_info(commit, &refs),
+        });
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+    }
+
+    let edges = pending
+        .into_iter()
+        .map(|(from, from_row, from_column, to, lane)| {
+            let target = positions.get(&to);
+            GraphEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                from_row,
+                from_column,
+                to_row: target.map(|(r, _)| *r),
+                to_column: target.map(|(_, c)| *c).unwrap_or(lane),
+            }
+        })
+        .collect();
+
+    Ok(GraphLayout {
+        nodes,
+        edges,
+        columns,
+        truncated,
+    })
+}
+
+fn free_lane(lanes: &mut Vec<Option<git2::Oid>>) -> usize {
+    match lanes.iter().position(|l| l.is_none()) {
+        Some(c) => c,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
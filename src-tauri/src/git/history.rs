@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use chrono::{offset::Utc, DateTime, TimeZone};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+    pub parents: Vec<String>,
+    pub refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct LogFilter {
+    pub branch: Option<String>,
+    pub path: Option<String>,
+    pub author: Option<String>,
+}
+
+pub fn commit_log(
+    repository: &git2::Repository,
+    filter: &LogFilter,
+    skip: usize,
+    limit: usize,
+) -> Result<Vec<CommitInfo>, Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    match &filter.branch {
+        Some(branch) => revwalk.push(repository.revparse_single(branch)?.peel_to_commit()?.id())?,
+        None => revwalk.push_head()?,
+    }
+
+    let refs = ref_names(repository)?;
+    let author = filter.author.as_ref().map(|a| a.to_lowercase());
+    let mut commits = Vec::new();
+    let mut skipped = 0;
+    for oid in revwalk {
+        let commit = repository.find_commit(oid?)?;
+        if let Some(author) = &author {
+            let signature = commit.author();
+            let name = signature.name().unwrap_or_default().to_lowercase();
+            let email = signature.email().unwrap_or_default().to_lowercase();
+            if !name.contains(author.as_str()) && !email.contains(author.as_str()) {
+                continue;
+            }
+        }
+        if let Some(path) = &filter.path {
+            if !touches_path(&commit, path)? {
+                continue;
+            }
+        }
+        if skipped < skip {
+            skipped += 1;
+            continue;
+        }
+        commits.push(commit_info(&commit, &refs));
+        if commits.len() >= limit {
+            break;
+        }
+    }
+    Ok(commits)
+}
+
+pub fn latest_commits(
+    repository: &git2::Repository,
+    limit: usize,
+) -> Result<Vec<CommitInfo>, Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    revwalk.push_glob("refs/heads")?;
+
+    let refs = ref_names(repository)?;
+    let mut commits = Vec::new();
+    for oid in revwalk.take(limit) {
+        let commit = repository.find_commit(oid?)?;
+        commits.push(commit_info(&commit, &refs));
+    }
+    Ok(commits)
+}
+
+pub fn commit_info(commit: &git2::Commit, refs: &HashMap<git2::Oid, Vec<String>>) -> CommitInfo {
+    let author = commit.author();
+    CommitInfo {
+        sha: commit.id().to_string(),
+        author: author.name().map(|s| s.to_string()),
+        email: author.email().map(|s| s.to_string()),
+        date: Utc.timestamp_opt(commit.time().seconds(), 0).single(),
+        summary: commit.summary().map(|s| s.to_string()),
+        parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+        refs: refs.get(&commit.id()).cloned().unwrap_or_default(),
+    }
+}
+
+pub fn ref_names(repository: &git2::Repository) -> Result<HashMap<git2::Oid, Vec<String>>, Error> {
+    let mut refs: HashMap<git2::Oid, Vec<String>> = HashMap::new();
+    for reference in repository.references()? {
+        let reference = reference?;
+        let name = match reference.shorthand() {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+        if let Ok(commit) = reference.peel_to_commit() {
+            refs.entry(commit.id()).or_default().push(name);
+        }
+    }
+    Ok(refs)
+}
+
+fn touches_path(commit: &git2::Commit, path: &str) -> Result<bool, Error> {
+    let path = std::path::Path::new(path);
+    let entry_id = |tree: &git2::Tree| tree.get_path(path).ok().map(|e| e.id());
+    let current = entry_id(&commit.tree()?);
+    if commit.parent_count() == 0 {
+        return Ok(current.is_some());
+    }
+    let parent = entry_id(&commit.parent(0)?.tree()?);
+    Ok(current != parent)
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, path::Path};
+
+use super::cache;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+impl LanguageStats {
+    fn merge(&mut self, other: &LanguageStats) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LanguageTotal {
+    #[serde(flatten)]
+    pub stats: LanguageStats,
+    pub projects: usize,
+}
+
+struct Language {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+const fn language(
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+) -> Language {
+    Language {
+        name,
+        extensions,
+        line_comments,
+        block_comment,
+    }
+}
+
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+const XML_BLOCK: Option<(&str, &str)> = Some(("<!--", "-->"));
+
+const LANGUAGES: [Language; 31] = [
+    language("Rust", &["rs"], &["//"], C_BLOCK),
+    language("TypeScript", &["ts", "tsx", "mts", "cts"], &["//"], C_BLOCK),
+    language("JavaScript", &["js", "jsx", "mjs", "cjs"], &["//"], C_BLOCK),
+    language("Python", &["py", "pyi"], &["#"], None),
+    language("Go", &["go"], &["//"], C_BLOCK),
+    language("C", &["c", "h"], &["//"], C_BLOCK),
+    language(
+        "C++",
+        &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        &["//"],
+        C_BLOCK,
+    ),
+    language("C#", &["cs"], &["//"], C_BLOCK),
+    language("Java", &["java"], &["//"], C_BLOCK),
+    language("Kotlin", &["kt", "kts"], &["//"], C_BLOCK),
+    language("Swift", &["swift"], &["//"], C_BLOCK),
+    language("Ruby", &["rb"], &["#"], None),
+    language("PHP", &["php"], &["//", "#"], C_BLOCK),
+    language("Shell", &["sh", "bash", "zsh"], &["#"], None),
+    language("PowerShell", &["ps1", "psm1"], &["#"], Some(("<#", "#>"))),
+    language("Lua", &["lua"], &["--"], Some(("--[[", "]]"))),
+    language("SQL", &["sql"], &["--"], C_BLOCK),
+    language("HTML", &["html", "htm"], &[], XML_BLOCK),
+    language("XML", &["xml", "svg"], &[], XML_BLOCK),
+    language("Vue", &["vue"], &["//"], XML_BLOCK),
+    language("Svelte", &["svelte"], &["//"], XML_BLOCK),
+    language("CSS", &["css"], &[], C_BLOCK),
+    language("SCSS", &["scss", "sass", "less"], &["//"], C_BLOCK),
+    language("JSON", &["json"], &[], None),
+    language("YAML", &["yml", "yaml"], &["#"], None),
+    language("TOML", &["toml"], &["#"], None),
+    language("Markdown", &["md", "markdown"], &[], XML_BLOCK),
+    language("Dockerfile", &["dockerfile"], &["#"], None),
+    language("Makefile", &["mk", "makefile"], &["#"], None),
+    language("Dart", &["dart"], &["//"], C_BLOCK),
+    language("Haskell", &["hs"], &["--"], Some(("{-", "-}"))),
+];
+
+const CACHE_NAME: &str = "languages.json";
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+pub fn language_stats(
+    cache_dir: &Path,
+    repository: &git2::Repository,
+) -> Result<Vec<LanguageStats>, git2::Error> {
+    let key = match cache::head_key(repository) {
+        Some(k) => k,
+        None => return Ok(Vec::new()),
+    };
+    if let Some(stats) = cache::read(cache_dir, repository, CACHE_NAME, &key) {
+        return Ok(stats);
+    }
+
+    let index = repository.index()?;
+    let mut stats: HashMap<&str, LanguageStats> = HashMap::new();
+    for entry in index.iter() {
+        if entry.mode & 0o170000 != 0o100000 || entry.flags & INDEX_STAGE_MASK != 0 {
+            continue;
+        }
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        let language = match detect_language(name) {
+            Some(l) => l,
+            None => continue,
+        };
+        let blob = match repository.find_blob(entry.id) {
+            Ok(b) => b,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let stat = stats.entry(language.name).or_insert_with(|| LanguageStats {
+            language: language.name.to_string(),
+            ..Default::default()
+        });
+        stat.files += 1;
+        stat.bytes += blob.size() as u64;
+        if !blob.is_binary() {
+            count_lines(language, &String::from_utf8_lossy(blob.content()), stat);
+        }
+    }
+
+    let mut stats = stats.into_values().collect::<Vec<LanguageStats>>();
+    stats.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.language.cmp(&b.language)));
+    if let Err(e) = cache::write(cache_dir, repository, CACHE_NAME, &key, &stats) {
+        log::warn!("[language_stats] {:?}", e);
+    }
+    Ok(stats)
+}
+
+pub fn summarize<I>(projects: I) -> Vec<LanguageTotal>
+where
+    I: Iterator<Item = Vec<LanguageStats>>,
+{
+    let mut totals: HashMap<String, LanguageTotal> = HashMap::new();
+    for stats in projects {
+        for stat in stats.iter() {
+            let total = totals
+                .entry(stat.language.clone())
+                .or_insert_with(|| LanguageTotal {
+                    stats: LanguageStats {
+                        language: stat.language.clone(),
+                        ..Default::default()
+                    },
+                    projects: 0,
+                });
+            total.stats.merge(stat);
+            total.projects += 1;
+        }
+    }
+    let mut totals = totals.into_values().collect::<Vec<LanguageTotal>>();
+    totals.sort_by(|a, b| {
+        b.stats
+            .bytes
+            .cmp(&a.stats.bytes)
+            .then(a.stats.language.cmp(&b.stats.language))
+    });
+    totals
+}
+
+fn detect_language(name: &str) -> Option<&'static Language> {
+    let lower = name.to_lowercase();
+    let extension = match lower.rsplit_once('.') {
+        Some((_, e)) => e,
+        None => lower.as_str(),
+    };
+    LANGUAGES.iter().find(|l| l.extensions.contains(&extension))
+}
+
+fn count_lines(language: &Language, content: &str, stats: &mut LanguageStats) {
+    let mut in_block: Option<&str> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(end) = in_block {
+            stats.comment += 1;
+            if line.contains(end) {
+                in_block = None;
+            }
+            continue;
+        }
+        if line.is_empty() {
+            stats.blank += 1;
+            continue;
+        }
+        if language.line_comments.iter().any(|c| line.starts_with(c)) {
+            if let Some((start, end)) = language.block_comment {
+                if line.starts_with(start) && !line[start.len()..].contains(end) {
+                    in_block = Some(end);
+                }
+            }
+            stats.comment += 1;
+            continue;
+        }
+        match language.block_comment {
+            Some((start, end)) if line.starts_with(start) => {
+                stats.comment += 1;
+                if !line[start.len()..].contains(end) {
+                    in_block = Some(end);
+                }
+            }
+            _ => stats.code += 1,
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("Maintenance was cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ObjectStats {
+    pub loose_objects: usize,
+    pub loose_size: u64,
+    pub packs: usize,
+    pub pack_size: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PackInfo {
+    pub name: String,
+    pub size: u64,
+    pub index_size: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LargeBlob {
+    pub sha: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+    pub commit: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MaintenanceReport {
+    pub objects: ObjectStats,
+    pub pack_files: Vec<PackInfo>,
+    pub largest_blobs: Vec<LargeBlob>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RepackResult {
+    pub packed_objects: usize,
+    pub removed_loose: usize,
+    pub removed_packs: usize,
+}
+
+const MAX_PATHS_PER_BLOB: usize = 5;
+
+pub fn object_stats(git_dir: &Path) -> Result<ObjectStats, Error> {
+    let mut stats = ObjectStats::default();
+    for (_, path) in loose_objects(git_dir)? {
+        stats.loose_objects += 1;
+        stats.loose_size += fs::metadata(path)?.len();
+    }
+    for pack in pack_files(git_dir)? {
+        stats.packs += 1;
+        stats.pack_size += pack.size + pack.index_size;
+    }
+    Ok(stats)
+}
+
+pub fn pack_files(git_dir: &Path) -> Result<Vec<PackInfo>, Error> {
+    let dir = git_dir.join("objects").join("pack");
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut packs = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().map(|e| e != "pack").unwrap_or(true) {
+            continue;
+        }
+        let index_size = fs::metadata(path.with_extension("idx"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        packs.push(PackInfo {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: fs::metadata(&path)?.len(),
+            index_size,
+        });
+    }
+    packs.sort_by_key(|p| Reverse(p.size));
+    Ok(packs)
+}
+
+pub fn largest_blobs(repository: &git2::Repository, limit: usize) -> Result<Vec<LargeBlob>, Error> {
+    let odb = repository.odb()?;
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push_glob("refs/*")?;
+
+    let mut seen_trees: HashSet<git2::Oid> = HashSet::new();
+    let mut blobs: HashMap<git2::Oid, LargeBlob> = HashMap::new();
+    for oid in revwalk {
+        let commit = repository.find_commit(oid?)?;
+        let mut pending = vec![(commit.tree()?, String::new())];
+        while let Some((tree, prefix)) = pending.pop() {
+            if !seen_trees.insert(tree.id()) {
+                continue;
+            }
+            for entry in tree.iter() {
+                let path = format!("{}{}", prefix, entry.name().unwrap_or_default());
+                match entry.kind() {
+                    Some(git2::ObjectType::Tree) => {
+                        pending.push((repository.find_tree(entry.id())?, path + "/"));
+                    }
+                    Some(git2::ObjectType::Blob) => {
+                        let blob = match blobs.get_mut(&entry.id()) {
+                            Some(b) => b,
+                            None => {
+                                let (size, _) = odb.read_header(entry.id())?;
+                                blobs.entry(entry.id()).or_insert(LargeBlob {
+                                    sha: entry.id().to_string(),
+                                    size: size as u64,
+                                    paths: Vec::new(),
+                                    commit: commit.id().to_string(),
+                                })
+                            }
+                        };
+                        if blob.paths.len() < MAX_PATHS_PER_BLOB && !blob.paths.contains(&path) {
+                            blob.paths.push(path);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    let mut blobs = blobs.into_values().collect::<Vec<LargeBlob>>();
+    blobs.sort_by_key(|b| Reverse(b.size));
+    blobs.truncate(limit);
+    Ok(blobs)
+}
+
+pub fn report(repository: &git2::Repository, limit: usize) -> Result<MaintenanceReport, Error> {
+    Ok(MaintenanceReport {
+        objects: object_stats(repository.path())?,
+        pack_files: pack_files(repository.path())?,
+        largest_blobs: largest_blobs(repository, limit)?,
+    })
+}
+
+pub fn gc(repository: &git2::Repository, cancel: &AtomicBool) -> Result<RepackResult, Error> {
+    let loose = loose_objects(repository.path())?;
+    let mut builder = repository.packbuilder()?;
+    for (oid, _) in loose.iter() {
+        builder.insert_object(*oid, None)?;
+    }
+    let packed_objects = write_pack(repository, &mut builder, cancel)?;
+    let removed_loose = remove_loose(&loose);
+    log::info!("[gc] packed {} loose objects", removed_loose);
+    Ok(RepackResult {
+        packed_objects,
+        removed_loose,
+        removed_packs: 0,
+    })
+}
+
+pub fn repack(repository: &git2::Repository, cancel: &AtomicBool) -> Result<RepackResult, Error> {
+    let loose = loose_objects(repository.path())?;
+    let packs = pack_files(repository.path())?;
+    let mut builder = repository.packbuilder()?;
+    let mut revwalk = repository.revwalk()?;
+    let odb = repository.odb()?;
+    for root in roots(repository)? {
+        if !odb.exists(root) {
+            continue;
+        }
+        builder.insert_recursive(root, None)?;
+        if let Ok(commit) = repository.find_commit(root) {
+            revwalk.push(commit.id())?;
+        }
+    }
+    builder.insert_walk(&mut revwalk)?;
+
+    let mut objects = Vec::new();
+    odb.foreach(|oid| {
+        objects.push(*oid);
+        true
+    })?;
+    for oid in objects {
+        builder.insert_object(oid, None)?;
+    }
+    let packed_objects = write_pack(repository, &mut builder, cancel)?;
+
+    let written = pack_files(repository.path())?;
+    let mut removed_packs = 0;
+    if written
+        .iter()
+        .any(|w| !packs.iter().any(|p| p.name == w.name))
+    {
+        let dir = repository.path().join("objects").join("pack");
+        for pack in packs.iter() {
+            let path = dir.join(&pack.name);
+            if path.with_extension("keep").exists() || path.with_extension("promisor").exists() {
+                continue;
+            }
+            for extension in ["pack", "idx", "rev", "bitmap", "mtimes"] {
+                let _ = fs::remove_file(path.with_extension(extension));
+            }
+            removed_packs += 1;
+        }
+        if removed_packs > 0 {
+            remove_multi_pack_index(&dir)?;
+        }
+    }
+    let removed_loose = remove_loose(&loose);
+    log::info!(
+        "[repack] packed {} objects, removed {} packs",
+        packed_objects,
+        removed_packs
+    );
+    Ok(RepackResult {
+        packed_objects,
+        removed_loose,
+        removed_packs,
+    })
+}
+
+fn roots(repository: &git2::Repository) -> Result<Vec<git2::Oid>, Error> {
+    let mut roots = Vec::new();
+    for reference in repository.references()? {
+        let reference = reference?;
+        if let Some(target) = reference.target() {
+            roots.push(target);
+        }
+        if let Some(name) = reference.name() {
+            roots.extend(repository.reflog(name)?.iter().map(|e| e.id_new()));
+        }
+    }
+    worktree_roots(repository, &mut roots)?;
+    for name in repository.worktrees()?.iter().flatten() {
+        let worktree = repository
+            .find_worktree(name)
+            .and_then(|w| git2::Repository::open_from_worktree(&w));
+        match worktree {
+            Ok(w) => worktree_roots(&w, &mut roots)?,
+            Err(e) => log::warn!("[repack] worktree {}: {:?}", name, e),
+        }
+    }
+    roots.retain(|oid| !oid.is_zero());
+    roots.sort();
+    roots.dedup();
+    Ok(roots)
+}
+
+fn worktree_roots(repository: &git2::Repository, roots: &mut Vec<git2::Oid>) -> Result<(), Error> {
+    if let Some(head) = repository.head().ok().and_then(|h| h.target()) {
+        roots.push(head);
+    }
+    roots.extend(repository.reflog("HEAD")?.iter().map(|e| e.id_new()));
+    if let Ok(index) = repository.index() {
+        roots.extend(index.iter().map(|e| e.id));
+    }
+    Ok(())
+}
+
+fn remove_multi_pack_index(dir: &Path) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_midx = path
+            .file_name()
+            .map(|n| n.to_string_lossy().starts_with("multi-pack-index"))
+            .unwrap_or(false);
+        if is_midx {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_pack<'a>(
+    repository: &git2::Repository,
+    builder: &mut git2::PackBuilder<'a>,
+    cancel: &'a AtomicBool,
+) -> Result<usize, Error> {
+    let count = builder.object_count();
+    if count == 0 {
+        return Ok(0);
+    }
+    builder.set_progress_callback(|_, _, _| !cancel.load(Ordering::SeqCst))?;
+    let odb = repository.odb()?;
+    let mut writer = odb.packwriter()?;
+    let written =
+        builder.foreach(|chunk| !cancel.load(Ordering::SeqCst) && writer.write_all(chunk).is_ok());
+    if cancel.load(Ordering::SeqCst) {
+        return Err(Error::Cancelled);
+    }
+    written?;
+    writer.commit()?;
+    Ok(count)
+}
+
+fn loose_objects(git_dir: &Path) -> Result<Vec<(git2::Oid, PathBuf)>, Error> {
+    let mut objects = Vec::new();
+    for entry in fs::read_dir(git_dir.join("objects"))? {
+        let entry = entry?;
+        let fanout = entry.file_name().to_string_lossy().to_string();
+        let is_fanout = fanout.len() == 2 && fanout.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_fanout || !entry.path().is_dir() {
+            continue;
+        }
+        for object in fs::read_dir(entry.path())? {
+            let object = object?;
+            let name = format!("{}{}", fanout, object.file_name().to_string_lossy());
+            if let Ok(oid) = git2::Oid::from_str(&name) {
+                objects.push((oid, object.path()));
+            }
+        }
+    }
+    Ok(objects)
+}
+
+fn remove_loose(objects: &[(git2::Oid, PathBuf)]) -> usize {
+    objects
+        .iter()
+        .filter(|(_, path)| match fs::remove_file(path) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("[remove_loose] {:?}: {:?}", path, e);
+                false
+            }
+        })
+        .count()
+}
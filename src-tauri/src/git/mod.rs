@@ -0,0 +1,18 @@
+pub mod branch;
+pub mod cache;
+pub mod cleanup;
+pub mod clone;
+pub mod commit;
+pub mod credentials;
+pub mod diff;
+pub mod graph;
+pub mod history;
+pub mod languages;
+pub mod maintenance;
+pub mod remote;
+pub mod secrets;
+pub mod stage;
+pub mod stash;
+pub mod sync;
+pub mod tag;
+pub mod todos;
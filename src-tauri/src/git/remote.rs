@@ -0,0 +1,200 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::github::user::Repository;
+use crate::project::RemoteUrlType;
+
+use super::credentials;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("HEAD does not point to a branch")]
+    DetachedHead,
+    #[error("Remote rejected {0}: {1}")]
+    PushRejected(String, String),
+    #[error("No free remote name")]
+    NoRemoteName,
+}
+
+const PUBLISH_REMOTE_NAMES: [&str; 2] = ["origin", "github"];
+
+pub fn remote_url(repository: &Repository, url_type: &RemoteUrlType) -> Option<String> {
+    match url_type {
+        RemoteUrlType::HTTP => repository
+            .url
+            .clone()
+            .map(|u| String::from("https://") + u.as_str() + ".git"),
+        RemoteUrlType::SSH => repository.ssh_url.clone(),
+    }
+}
+
+pub fn publish_remote(
+    repository: &git2::Repository,
+    published: &Repository,
+    url_type: &RemoteUrlType,
+) -> Result<(String, bool), Error> {
+    let urls = [RemoteUrlType::HTTP, RemoteUrlType::SSH]
+        .iter()
+        .filter_map(|t| remote_url(published, t))
+        .collect::<Vec<String>>();
+    for name in remote_names(repository)? {
+        let remote = repository.find_remote(&name)?;
+        if remote
+            .url()
+            .map(|u| urls.iter().any(|url| url == u))
+            .unwrap_or(false)
+        {
+            return Ok((name, false));
+        }
+    }
+    let url = remote_url(published, url_type).ok_or(Error::NoRemoteName)?;
+    let name = PUBLISH_REMOTE_NAMES
+        .iter()
+        .find(|name| repository.find_remote(name).is_err())
+        .ok_or(Error::NoRemoteName)?;
+    repository.remote(name, &url)?;
+    Ok((name.to_string(), true))
+}
+
+pub fn current_branch(repository: &git2::Repository) -> Result<String, Error> {
+    let head = repository.head()?;
+    if !head.is_branch() {
+        return Err(Error::DetachedHead);
+    }
+    match head.shorthand() {
+        Some(name) => Ok(name.to_string()),
+        None => Err(Error::DetachedHead),
+    }
+}
+
+pub fn fetch(
+    repository: &git2::Repository,
+    remote_name: &str,
+    token: Option<String>,
+) -> Result<(), Error> {
+    fetch_cancellable(repository, remote_name, token, &AtomicBool::new(false))
+}
+
+pub fn fetch_cancellable(
+    repository: &git2::Repository,
+    remote_name: &str,
+    token: Option<String>,
+    cancel: &AtomicBool,
+) -> Result<(), Error> {
+    let mut remote = repository.find_remote(remote_name)?;
+    let mut callbacks = credentials::remote_callbacks(token);
+    callbacks.transfer_progress(|_| !cancel.load(Ordering::SeqCst));
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    remote.fetch(&[] as &[&str], Some(&mut options), None)?;
+    log::info!("[fetch] fetched {}", remote_name);
+    Ok(())
+}
+
+pub fn remote_names(repository: &git2::Repository) -> Result<Vec<String>, Error> {
+    let remotes = repository.remotes()?;
+    Ok(remotes.iter().flatten().map(|r| r.to_string()).collect())
+}
+
+pub fn stale_tracking_refs(
+    repository: &git2::Repository,
+    remote_name: &str,
+    token: Option<String>,
+) -> Result<Vec<String>, Error> {
+    let mut remote = repository.find_remote(remote_name)?;
+    let callbacks = credentials::remote_callbacks(token);
+    let connection = remote.connect_auth(git2::Direction::Fetch, Some(callbacks), None)?;
+    let prefix = format!("refs/remotes/{}/", remote_name);
+    let live = connection
+        .list()?
+        .iter()
+        .filter_map(|head| head.name().strip_prefix("refs/heads/"))
+        .map(|name| prefix.clone() + name)
+        .collect::<Vec<String>>();
+    drop(connection);
+
+    let stale = repository
+        .references_glob(&(prefix.clone() + "*"))?
+        .flatten()
+        .filter_map(|r| r.name().map(|n| n.to_string()))
+        .filter(|name| *name != prefix.clone() + "HEAD" && !live.contains(name))
+        .collect();
+    Ok(stale)
+}
+
+pub fn prune(
+    repository: &git2::Repository,
+    remote_name: &str,
+    token: Option<String>,
+) -> Result<Vec<String>, Error> {
+    let stale = stale_tracking_refs(repository, remote_name, token)?;
+    for name in stale.iter() {
+        repository.find_reference(name)?.delete()?;
+    }
+    log::info!("[prune] pruned {} refs from {}", stale.len(), remote_name);
+    Ok(stale)
+}
+
+pub fn delete_remote_branch(
+    repository: &git2::Repository,
+    remote_name: &str,
+    branch: &str,
+    token: Option<String>,
+) -> Result<(), Error> {
+    let refspec = format!(":refs/heads/{}", branch);
+    push_refspec(repository, remote_name, &refspec, token)?;
+    log::info!(
+        "[delete_remote_branch] deleted {} on {}",
+        branch,
+        remote_name
+    );
+    Ok(())
+}
+
+pub fn push_branch(
+    repository: &git2::Repository,
+    remote_name: &str,
+    branch: &str,
+    token: Option<String>,
+    set_upstream: bool,
+    force: bool,
+) -> Result<(), Error> {
+    let prefix = if force { "+" } else { "" };
+    let refspec = format!("{0}refs/heads/{1}:refs/heads/{1}", prefix, branch);
+    push_refspec(repository, remote_name, &refspec, token)?;
+
+    if set_upstream {
+        let mut local = repository.find_branch(branch, git2::BranchType::Local)?;
+        local.set_upstream(Some(&format!("{}/{}", remote_name, branch)))?;
+    }
+    log::info!("[push_branch] pushed {} to {}", branch, remote_name);
+
+    Ok(())
+}
+
+pub fn push_refspec(
+    repository: &git2::Repository,
+    remote_name: &str,
+    refspec: &str,
+    token: Option<String>,
+) -> Result<(), Error> {
+    let mut remote = repository.find_remote(remote_name)?;
+    let mut rejected: Option<(String, String)> = None;
+    {
+        let mut callbacks = credentials::remote_callbacks(token);
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(message) = status {
+                rejected = Some((refname.to_string(), message.to_string()));
+            }
+            Ok(())
+        });
+        let mut options = git2::PushOptions::new();
+        options.remote_callbacks(callbacks);
+        remote.push(&[refspec], Some(&mut options))?;
+    }
+    match rejected {
+        Some((refname, message)) => Err(Error::PushRejected(refname, message)),
+        None => Ok(()),
+    }
+}
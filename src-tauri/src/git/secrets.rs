@@ -0,0 +1,254 @@
+use std::{collections::HashMap, path::Path};
+
+use regex::Regex;
+
+use super::diff;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    DiffError(#[from] diff::Error),
+    #[error("Invalid rule {0}: {1}")]
+    InvalidRule(String, regex::Error),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SecretRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SecretFinding {
+    pub rule: String,
+    pub path: String,
+    pub line: Option<u32>,
+    pub commit: Option<String>,
+    pub excerpt: String,
+}
+
+const BUILTIN_RULES: [(&str, &str); 9] = [
+    ("AWS access key", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "GitHub token",
+        r"\b(gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b",
+    ),
+    ("GitLab token", r"\bglpat-[A-Za-z0-9_-]{20,}\b"),
+    ("Slack token", r"\bxox[baprs]-[A-Za-z0-9-]{10,}\b"),
+    ("Google API key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    ("Stripe secret key", r"\b[sr]k_live_[0-9A-Za-z]{24,}\b"),
+    (
+        "Private key",
+        r"-----BEGIN (RSA |EC |DSA |OPENSSH |PGP |ENCRYPTED )?PRIVATE KEY( BLOCK)?-----",
+    ),
+    (
+        "Credential assignment",
+        r#"(?i)\b[a-z0-9_.-]*(api[_-]?key|secret|token|passwd|password)[a-z0-9_.-]*\s*[:=]\s*["'][^"'\s]{8,}["']"#,
+    ),
+    (
+        "Credentials in URL",
+        r"[a-zA-Z][a-zA-Z0-9+.-]*://[^/\s:@]+:[^/\s:@]{3,}@[^\s]+",
+    ),
+];
+
+const SENSITIVE_FILES: [&str; 9] = [
+    ".env",
+    ".pypirc",
+    ".netrc",
+    "id_rsa",
+    "id_dsa",
+    "id_ecdsa",
+    "id_ed25519",
+    "credentials.json",
+    "secrets.json",
+];
+const SENSITIVE_EXTENSIONS: [&str; 6] = ["pem", "key", "p12", "pfx", "jks", "keystore"];
+const ENV_TEMPLATE_SUFFIXES: [&str; 4] = [".example", ".sample", ".template", ".dist"];
+
+const HIGH_ENTROPY_RULE: &str = "High-entropy string";
+const MIN_ENTROPY_LENGTH: usize = 32;
+const MIN_ENTROPY: f64 = 4.5;
+const MAX_LINE_LENGTH: usize = 4096;
+
+pub struct Scanner {
+    rules: Vec<(String, Regex)>,
+    token: Regex,
+}
+
+impl Scanner {
+    pub fn new(custom: &[SecretRule]) -> Result<Self, Error> {
+        let mut rules = Vec::new();
+        for (name, pattern) in BUILTIN_RULES.iter() {
+            let regex = Regex::new(pattern).map_err(|e| Error::InvalidRule(name.to_string(), e))?;
+            rules.push((name.to_string(), regex));
+        }
+        for rule in custom.iter() {
+            let regex =
+                Regex::new(&rule.pattern).map_err(|e| Error::InvalidRule(rule.name.clone(), e))?;
+            rules.push((rule.name.clone(), regex));
+        }
+        Ok(Self {
+            rules,
+            token: Regex::new(r"[A-Za-z0-9+/=_-]{32,}").unwrap(),
+        })
+    }
+
+    pub fn scan_worktree(
+        &self,
+        repository: &git2::Repository,
+    ) -> Result<Vec<SecretFinding>, Error> {
+        let diff = diff::diff(repository, &diff::DiffTarget::All, None)?;
+        self.scan_diff(&diff, None)
+    }
+
+    pub fn scan_unpushed(
+        &self,
+        repository: &git2::Repository,
+    ) -> Result<Vec<SecretFinding>, Error> {
+        let mut revwalk = repository.revwalk()?;
+        revwalk.push_head()?;
+        let upstream = repository
+            .head()
+            .ok()
+            .and_then(|h| h.shorthand().map(String::from))
+            .and_then(|b| repository.find_branch(&b, git2::BranchType::Local).ok())
+            .and_then(|b| b.upstream().ok())
+            .and_then(|u| u.get().target());
+        match upstream {
+            Some(oid) => revwalk.hide(oid)?,
+            None => revwalk.hide_glob("refs/remotes/*")?,
+        }
+
+        let mut findings = Vec::new();
+        for oid in revwalk {
+            let commit = repository.find_commit(oid?)?;
+            let parent = match commit.parent(0) {
+                Ok(p) => Some(p.tree()?),
+                Err(_) => None,
+            };
+            let mut options = git2::DiffOptions::new();
+            options.max_size(diff::MAX_FILE_BYTES);
+            let diff = repository.diff_tree_to_tree(
+                parent.as_ref(),
+                Some(&commit.tree()?),
+                Some(&mut options),
+            )?;
+            findings.extend(self.scan_diff(&diff, Some(commit.id().to_string()))?);
+        }
+        Ok(findings)
+    }
+
+    fn scan_diff(
+        &self,
+        diff: &git2::Diff,
+        commit: Option<String>,
+    ) -> Result<Vec<SecretFinding>, Error> {
+        let mut findings = Vec::new();
+        for delta in diff.deltas() {
+            if delta.status() == git2::Delta::Deleted {
+                continue;
+            }
+            if let Some(path) = delta.new_file().path() {
+                if is_sensitive_file(path) {
+                    findings.push(SecretFinding {
+                        rule: String::from("Sensitive file"),
+                        path: path.to_string_lossy().to_string(),
+                        line: None,
+                        commit: commit.clone(),
+                        excerpt: String::new(),
+                    });
+                }
+            }
+        }
+
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            None,
+            Some(&mut |delta, _, line| {
+                if line.origin() != '+' {
+                    return true;
+                }
+                let path = match delta.new_file().path() {
+                    Some(p) => p.to_string_lossy().to_string(),
+                    None => return true,
+                };
+                let content = String::from_utf8_lossy(line.content());
+                if content.len() > MAX_LINE_LENGTH {
+                    return true;
+                }
+                for (rule, excerpt) in self.scan_line(&content) {
+                    findings.push(SecretFinding {
+                        rule,
+                        path: path.clone(),
+                        line: line.new_lineno(),
+                        commit: commit.clone(),
+                        excerpt,
+                    });
+                }
+                true
+            }),
+        )?;
+        Ok(findings)
+    }
+
+    fn scan_line(&self, content: &str) -> Vec<(String, String)> {
+        let mut matches = Vec::new();
+        for (name, regex) in self.rules.iter() {
+            if let Some(m) = regex.find(content) {
+                matches.push((name.clone(), redact(m.as_str())));
+            }
+        }
+        if matches.is_empty() {
+            if let Some(m) = self.token.find_iter(content).find(|m| {
+                m.as_str().len() >= MIN_ENTROPY_LENGTH && entropy(m.as_str()) >= MIN_ENTROPY
+            }) {
+                matches.push((String::from(HIGH_ENTROPY_RULE), redact(m.as_str())));
+            }
+        }
+        matches
+    }
+}
+
+fn is_sensitive_file(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(n) => n,
+        None => return false,
+    };
+    if name.starts_with(".env") {
+        return !ENV_TEMPLATE_SUFFIXES.iter().any(|s| name.ends_with(s));
+    }
+    if SENSITIVE_FILES.contains(&name) {
+        return true;
+    }
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SENSITIVE_EXTENSIONS.contains(&e))
+        .unwrap_or(false)
+}
+
+fn entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let length = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / length;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn redact(secret: &str) -> String {
+    let visible = secret.chars().take(4).collect::<String>();
+    format!(
+        "{}{}",
+        visible,
+        "*".repeat(secret.chars().count().min(12).saturating_sub(4))
+    )
+}
@@ -0,0 +1,150 @@
+use std::{fs, path::Path};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Repository has no working directory")]
+    Bare,
+    #[error("No hunk at -{0} +{1} in {2}")]
+    NoHunk(u32, u32, String),
+}
+
+pub fn stage_paths(repository: &git2::Repository, paths: &[String]) -> Result<(), Error> {
+    let mut index = repository.index()?;
+    index.add_all(paths.iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.update_all(paths.iter(), None)?;
+    index.write()?;
+    Ok(())
+}
+
+pub fn unstage_paths(repository: &git2::Repository, paths: &[String]) -> Result<(), Error> {
+    match repository
+        .head()
+        .and_then(|h| h.peel(git2::ObjectType::Commit))
+    {
+        Ok(head) => repository.reset_default(Some(&head), paths.iter())?,
+        Err(_) => {
+            let mut index = repository.index()?;
+            index.remove_all(paths.iter(), None)?;
+            index.write()?;
+        }
+    }
+    Ok(())
+}
+
+pub fn stage_hunk(
+    repository: &git2::Repository,
+    path: &str,
+    old_start: u32,
+    new_start: u32,
+) -> Result<(), Error> {
+    let mut options = git2::DiffOptions::new();
+    options.pathspec(path).disable_pathspec_match(true);
+    let diff = repository.diff_index_to_workdir(None, Some(&mut options))?;
+    apply_hunk(repository, &diff, path, old_start, new_start)
+}
+
+pub fn unstage_hunk(
+    repository: &git2::Repository,
+    path: &str,
+    old_start: u32,
+    new_start: u32,
+) -> Result<(), Error> {
+    let head = match repository.head() {
+        Ok(h) => Some(h.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let mut options = git2::DiffOptions::new();
+    options
+        .pathspec(path)
+        .disable_pathspec_match(true)
+        .reverse(true);
+    let diff = repository.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?;
+    apply_hunk(repository, &diff, path, new_start, old_start)
+}
+
+pub fn discard_paths(repository: &git2::Repository, paths: &[String]) -> Result<(), Error> {
+    let workdir = repository.workdir().ok_or(Error::Bare)?;
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .disable_pathspec_match(true);
+    for path in paths {
+        options.pathspec(path);
+    }
+    let statuses = repository.statuses(Some(&mut options))?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force().update_index(false);
+    let mut tracked = 0;
+    for entry in statuses.iter() {
+        let path = match entry.path() {
+            Some(p) => p,
+            None => continue,
+        };
+        if entry.status() == git2::Status::WT_NEW {
+            let full = workdir.join(path);
+            fs::remove_file(&full)?;
+            remove_empty_parents(workdir, &full);
+        } else {
+            checkout.path(literal_pathspec(path));
+            tracked += 1;
+        }
+    }
+    if tracked > 0 {
+        repository.checkout_index(None, Some(&mut checkout))?;
+    }
+    log::info!("[discard_paths] discarded {} files", statuses.len());
+    Ok(())
+}
+
+fn literal_pathspec(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '\\' | '*' | '?' | '[') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn remove_empty_parents(workdir: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        if d == workdir || fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+fn apply_hunk(
+    repository: &git2::Repository,
+    diff: &git2::Diff,
+    path: &str,
+    old_start: u32,
+    new_start: u32,
+) -> Result<(), Error> {
+    let mut found = false;
+    {
+        let mut options = git2::ApplyOptions::new();
+        options.hunk_callback(|hunk| {
+            let matches = hunk
+                .map(|h| h.old_start() == old_start && h.new_start() == new_start)
+                .unwrap_or(false);
+            found |= matches;
+            matches
+        });
+        repository.apply(diff, git2::ApplyLocation::Index, Some(&mut options))?;
+    }
+    if !found {
+        return Err(Error::NoHunk(old_start, new_start, path.to_string()));
+    }
+    Ok(())
+}
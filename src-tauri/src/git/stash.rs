@@ -0,0 +1,144 @@
+use chrono::{offset::Utc, DateTime, TimeZone};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("No stash at index {0}")]
+    NoStash(usize),
+    #[error("Applying stash {0} would overwrite local changes")]
+    WouldClobber(usize),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StashInfo {
+    pub index: usize,
+    pub message: String,
+    pub branch: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StashDiff {
+    pub index: usize,
+    pub files: Vec<String>,
+    pub patch: String,
+}
+
+pub fn list_stashes(repository: &mut git2::Repository) -> Result<Vec<StashInfo>, Error> {
+    let mut entries = Vec::new();
+    repository.stash_foreach(|index, message, oid| {
+        entries.push((index, message.to_string(), *oid));
+        true
+    })?;
+
+    let stashes = entries
+        .into_iter()
+        .map(|(index, message, oid)| {
+            let commit = repository.find_commit(oid).ok();
+            let date = commit
+                .as_ref()
+                .and_then(|c| Utc.timestamp_opt(c.time().seconds(), 0).single());
+            let files = match diff_stash(repository, oid) {
+                Ok(diff) => diff_files(&diff),
+                Err(e) => {
+                    log::warn!("[list_stashes] {:?}", e);
+                    Vec::new()
+                }
+            };
+            StashInfo {
+                index,
+                branch: stash_branch(&message),
+                message,
+                date,
+                files,
+            }
+        })
+        .collect();
+    Ok(stashes)
+}
+
+pub fn stash_diff(repository: &mut git2::Repository, index: usize) -> Result<StashDiff, Error> {
+    let oid = stash_oid(repository, index)?;
+    let diff = diff_stash(repository, oid)?;
+    let files = diff_files(&diff);
+    let mut patch = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        match line.origin() {
+            '+' | '-' | ' ' => patch.push(line.origin()),
+            _ => {}
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(StashDiff {
+        index,
+        files,
+        patch,
+    })
+}
+
+pub fn apply(repository: &mut git2::Repository, index: usize) -> Result<(), Error> {
+    stash_oid(repository, index)?;
+    let mut options = git2::StashApplyOptions::new();
+    match repository.stash_apply(index, Some(&mut options)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == git2::ErrorCode::Conflict => Err(Error::WouldClobber(index)),
+        Err(e) => Err(Error::GitError(e)),
+    }
+}
+
+pub fn pop(repository: &mut git2::Repository, index: usize) -> Result<(), Error> {
+    stash_oid(repository, index)?;
+    let mut options = git2::StashApplyOptions::new();
+    match repository.stash_pop(index, Some(&mut options)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == git2::ErrorCode::Conflict => Err(Error::WouldClobber(index)),
+        Err(e) => Err(Error::GitError(e)),
+    }
+}
+
+pub fn drop(repository: &mut git2::Repository, index: usize) -> Result<(), Error> {
+    stash_oid(repository, index)?;
+    repository.stash_drop(index)?;
+    Ok(())
+}
+
+fn stash_oid(repository: &mut git2::Repository, index: usize) -> Result<git2::Oid, Error> {
+    let mut found = None;
+    repository.stash_foreach(|i, _, oid| {
+        if i == index {
+            found = Some(*oid);
+        }
+        found.is_none()
+    })?;
+    found.ok_or(Error::NoStash(index))
+}
+
+fn diff_files(diff: &git2::Diff) -> Vec<String> {
+    diff.deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .filter_map(|p| p.to_str().map(|s| s.to_string()))
+        .collect()
+}
+
+fn diff_stash(repository: &git2::Repository, oid: git2::Oid) -> Result<git2::Diff<'_>, Error> {
+    let commit = repository.find_commit(oid)?;
+    let tree = commit.tree()?;
+    let base = commit.parent(0)?.tree()?;
+    let mut diff = repository.diff_tree_to_tree(Some(&base), Some(&tree), None)?;
+    if let Ok(untracked) = commit.parent(2) {
+        let untracked = untracked.tree()?;
+        let extra = repository.diff_tree_to_tree(None, Some(&untracked), None)?;
+        diff.merge(&extra)?;
+    }
+    Ok(diff)
+}
+
+fn stash_branch(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    rest.split(':').next().map(|b| b.to_string())
+}
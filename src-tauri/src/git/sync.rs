@@ -0,0 +1,260 @@
+use super::remote;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    RemoteError(#[from] remote::Error),
+    #[error("Worktree has uncommitted changes")]
+    DirtyWorktree,
+    #[error("Branch {0} has diverged from its upstream")]
+    Diverged(String),
+    #[error("Branch {0} has no upstream")]
+    NoUpstream(String),
+    #[error("Branch {0} must be checked out to be rebased")]
+    NotCheckedOut(String),
+    #[error("Rebase of {0} stopped on conflicts and was aborted")]
+    RebaseConflict(String),
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SyncOptions {
+    pub rebase: bool,
+    pub allow_dirty: bool,
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum SyncOutcome {
+    UpToDate,
+    FastForwarded,
+    Rebased,
+    Pushed,
+    ForcePushed,
+    Behind,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SyncResult {
+    pub branch: String,
+    pub remote: String,
+    pub old_commit: Option<String>,
+    pub new_commit: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub outcome: SyncOutcome,
+}
+
+pub fn pull(
+    repository: &git2::Repository,
+    branch: Option<&str>,
+    options: &SyncOptions,
+    token: Option<String>,
+) -> Result<SyncResult, Error> {
+    let branch = match branch {
+        Some(b) => b.to_string(),
+        None => remote::current_branch(repository)?,
+    };
+    let local = repository.find_branch(&branch, git2::BranchType::Local)?;
+    let is_head = local.is_head();
+    let remote_name = upstream_remote(repository, &branch)?;
+    if is_head && !options.allow_dirty && is_dirty(repository)? {
+        return Err(Error::DirtyWorktree);
+    }
+
+    remote::fetch(repository, &remote_name, token)?;
+
+    let local = repository.find_branch(&branch, git2::BranchType::Local)?;
+    let upstream = local
+        .upstream()
+        .map_err(|_| Error::NoUpstream(branch.clone()))?;
+    let local_oid = local
+        .get()
+        .target()
+        .ok_or(Error::NoUpstream(branch.clone()))?;
+    let upstream_oid = upstream
+        .get()
+        .target()
+        .ok_or(Error::NoUpstream(branch.clone()))?;
+    let (ahead, behind) = repository.graph_ahead_behind(local_oid, upstream_oid)?;
+    let mut result = SyncResult {
+        branch: branch.clone(),
+        remote: remote_name,
+        old_commit: Some(local_oid.to_string()),
+        new_commit: Some(local_oid.to_string()),
+        ahead,
+        behind,
+        outcome: SyncOutcome::UpToDate,
+    };
+    if behind == 0 {
+        return Ok(result);
+    }
+
+    if ahead == 0 {
+        if is_head {
+            let target = repository.find_object(upstream_oid, None)?;
+            repository.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+        }
+        let mut reference = local.into_reference();
+        reference.set_target(upstream_oid, &format!("pull: fast-forward {}", branch))?;
+        result.new_commit = Some(upstream_oid.to_string());
+        result.outcome = SyncOutcome::FastForwarded;
+        log::info!("[pull] fast-forwarded {} to {}", branch, upstream_oid);
+        return Ok(result);
+    }
+
+    if !options.rebase {
+        return Err(Error::Diverged(branch));
+    }
+    if !is_head {
+        return Err(Error::NotCheckedOut(branch));
+    }
+    let new_oid = rebase_onto(repository, &branch, &upstream)?;
+    result.new_commit = Some(new_oid.to_string());
+    result.outcome = SyncOutcome::Rebased;
+    log::info!("[pull] rebased {} onto {}", branch, upstream_oid);
+    Ok(result)
+}
+
+pub fn push(
+    repository: &git2::Repository,
+    branch: Option<&str>,
+    options: &SyncOptions,
+    token: Option<String>,
+) -> Result<SyncResult, Error> {
+    let branch = match branch {
+        Some(b) => b.to_string(),
+        None => remote::current_branch(repository)?,
+    };
+    let local = repository.find_branch(&branch, git2::BranchType::Local)?;
+    if local.is_head() && !options.allow_dirty && is_dirty(repository)? {
+        return Err(Error::DirtyWorktree);
+    }
+    let local_oid = local
+        .get()
+        .target()
+        .ok_or(Error::NoUpstream(branch.clone()))?;
+
+    let (remote_name, upstream_ref) = match upstream_remote(repository, &branch) {
+        Ok(r) => (r, Some(upstream_merge(repository, &branch)?)),
+        Err(Error::NoUpstream(_)) => (default_remote(repository, &branch)?, None),
+        Err(e) => return Err(e),
+    };
+
+    let mut result = SyncResult {
+        branch: branch.clone(),
+        remote: remote_name.clone(),
+        old_commit: None,
+        new_commit: Some(local_oid.to_string()),
+        ahead: 0,
+        behind: 0,
+        outcome: SyncOutcome::Pushed,
+    };
+    let upstream_ref = match upstream_ref {
+        Some(r) => r,
+        None => {
+            remote::push_branch(repository, &remote_name, &branch, token, true, false)?;
+            log::info!("[push] pushed {} to {}", branch, remote_name);
+            return Ok(result);
+        }
+    };
+
+    remote::fetch(repository, &remote_name, token.clone())?;
+    let local = repository.find_branch(&branch, git2::BranchType::Local)?;
+    let mut force = false;
+    if let Some(upstream_oid) = local.upstream().ok().and_then(|u| u.get().target()) {
+        let (ahead, behind) = repository.graph_ahead_behind(local_oid, upstream_oid)?;
+        result.old_commit = Some(upstream_oid.to_string());
+        result.ahead = ahead;
+        result.behind = behind;
+        if ahead == 0 {
+            result.outcome = match behind {
+                0 => SyncOutcome::UpToDate,
+                _ => SyncOutcome::Behind,
+            };
+            return Ok(result);
+        }
+        if behind > 0 {
+            if !options.force {
+                return Err(Error::Diverged(branch));
+            }
+            force = true;
+            result.outcome = SyncOutcome::ForcePushed;
+        }
+    }
+
+    let prefix = if force { "+" } else { "" };
+    let refspec = format!("{}refs/heads/{}:{}", prefix, branch, upstream_ref);
+    remote::push_refspec(repository, &remote_name, &refspec, token)?;
+    log::info!("[push] pushed {} to {}", branch, remote_name);
+    Ok(result)
+}
+
+pub fn is_dirty(repository: &git2::Repository) -> Result<bool, Error> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let statuses = repository.statuses(Some(&mut options))?;
+    Ok(statuses.iter().any(|s| s.status() != git2::Status::CURRENT))
+}
+
+fn upstream_remote(repository: &git2::Repository, branch: &str) -> Result<String, Error> {
+    let refname = format!("refs/heads/{}", branch);
+    let buf = repository
+        .branch_upstream_remote(&refname)
+        .map_err(|_| Error::NoUpstream(branch.to_string()))?;
+    match buf.as_str() {
+        Some(name) => Ok(name.to_string()),
+        None => Err(Error::NoUpstream(branch.to_string())),
+    }
+}
+
+fn upstream_merge(repository: &git2::Repository, branch: &str) -> Result<String, Error> {
+    repository
+        .config()?
+        .get_string(&format!("branch.{}.merge", branch))
+        .map_err(|_| Error::NoUpstream(branch.to_string()))
+}
+
+fn default_remote(repository: &git2::Repository, branch: &str) -> Result<String, Error> {
+    let remotes = repository.remotes()?;
+    let names = remotes.iter().flatten().collect::<Vec<&str>>();
+    if names.contains(&"origin") {
+        return Ok(String::from("origin"));
+    }
+    match names.first() {
+        Some(name) => Ok(name.to_string()),
+        None => Err(Error::NoUpstream(branch.to_string())),
+    }
+}
+
+fn rebase_onto(
+    repository: &git2::Repository,
+    branch: &str,
+    upstream: &git2::Branch,
+) -> Result<git2::Oid, Error> {
+    let upstream = repository.reference_to_annotated_commit(upstream.get())?;
+    let committer = repository.signature()?;
+    let mut rebase = repository.rebase(None, Some(&upstream), None, None)?;
+    while let Some(operation) = rebase.next() {
+        if let Err(e) = operation {
+            rebase.abort()?;
+            return Err(Error::GitError(e));
+        }
+        if repository.index()?.has_conflicts() {
+            rebase.abort()?;
+            return Err(Error::RebaseConflict(branch.to_string()));
+        }
+        if let Err(e) = rebase.commit(None, &committer, None) {
+            if e.code() != git2::ErrorCode::Applied {
+                rebase.abort()?;
+                return Err(Error::GitError(e));
+            }
+        }
+    }
+    rebase.finish(Some(&committer))?;
+
+    let head = repository.head()?;
+    head.target()
+        .ok_or(Error::NotCheckedOut(branch.to_string()))
+}
@@ -0,0 +1,126 @@
+use std::cmp::Reverse;
+
+use chrono::{offset::Utc, DateTime, TimeZone};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error("Tag message is empty")]
+    EmptyMessage,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TagInfo {
+    pub name: String,
+    pub annotated: bool,
+    pub target: String,
+    pub date: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommitsSinceTag {
+    pub tag: Option<String>,
+    pub commits: usize,
+}
+
+pub fn list_tags(repository: &git2::Repository) -> Result<Vec<TagInfo>, Error> {
+    let mut tags = Vec::new();
+    for name in repository.tag_names(None)?.iter().flatten() {
+        let reference = match repository.find_reference(&format!("refs/tags/{}", name)) {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("{:?}", e);
+                continue;
+            }
+        };
+        let commit = match reference.peel_to_commit() {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let tag = reference.peel_to_tag().ok();
+        let seconds = match tag.as_ref().and_then(|t| t.tagger()) {
+            Some(tagger) => tagger.when().seconds(),
+            None => commit.time().seconds(),
+        };
+        tags.push(TagInfo {
+            name: name.to_string(),
+            annotated: tag.is_some(),
+            target: commit.id().to_string(),
+            date: Utc.timestamp_opt(seconds, 0).single(),
+            message: tag.and_then(|t| t.message().map(|m| m.trim().to_string())),
+        });
+    }
+    tags.sort_by_key(|t| Reverse(t.date));
+    Ok(tags)
+}
+
+pub fn commits_since_last_tag(repository: &git2::Repository) -> Result<CommitsSinceTag, Error> {
+    let head = repository.head()?.peel_to_commit()?.id();
+    if let Some((tag, commits)) = nearest_tag(repository, head, true)? {
+        return Ok(CommitsSinceTag {
+            tag: Some(tag),
+            commits,
+        });
+    }
+
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push(head)?;
+    Ok(CommitsSinceTag {
+        tag: None,
+        commits: revwalk.count(),
+    })
+}
+
+pub fn last_tag_before(
+    repository: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<Option<String>, Error> {
+    Ok(nearest_tag(repository, commit, false)?.map(|(tag, _)| tag))
+}
+
+fn nearest_tag(
+    repository: &git2::Repository,
+    commit: git2::Oid,
+    include_self: bool,
+) -> Result<Option<(String, usize)>, Error> {
+    let mut best: Option<(String, usize)> = None;
+    for tag in list_tags(repository)? {
+        let oid = git2::Oid::from_str(&tag.target)?;
+        let reachable = if oid == commit {
+            include_self
+        } else {
+            repository.graph_descendant_of(commit, oid)?
+        };
+        if !reachable {
+            continue;
+        }
+        let mut revwalk = repository.revwalk()?;
+        revwalk.push(commit)?;
+        revwalk.hide(oid)?;
+        let commits = revwalk.count();
+        if best.as_ref().map(|(_, c)| commits < *c).unwrap_or(true) {
+            best = Some((tag.name, commits));
+        }
+    }
+    Ok(best)
+}
+
+pub fn create_tag(
+    repository: &git2::Repository,
+    name: &str,
+    target: &str,
+    message: &str,
+) -> Result<git2::Oid, Error> {
+    if message.trim().is_empty() {
+        return Err(Error::EmptyMessage);
+    }
+    let object = repository
+        .revparse_single(target)?
+        .peel(git2::ObjectType::Commit)?;
+    let tagger = repository.signature()?;
+    let oid = repository.tag(name, &object, &tagger, message, false)?;
+    log::info!("[create_tag] {} at {}", name, object.id());
+    Ok(oid)
+}
@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use chrono::{offset::Utc, DateTime, TimeZone};
+use regex::Regex;
+
+use super::cache;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TodoKind {
+    Todo,
+    Fixme,
+    Hack,
+    Xxx,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TodoItem {
+    pub kind: TodoKind,
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub commit: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct TodoFilter {
+    pub kinds: Vec<TodoKind>,
+    pub author: Option<String>,
+    pub path: Option<String>,
+    pub min_age_days: Option<i64>,
+}
+
+const CACHE_NAME: &str = "todos.json";
+const MAX_FILE_BYTES: usize = 1024 * 1024;
+const MAX_TEXT_CHARS: usize = 200;
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+// Blaming every matching file is too slow to run on each project scan, so TODOs
+// are only collected here when requested and cached per HEAD like the language
+// stats that the scan does attach.
+pub fn list_todos(cache_dir: &Path, repository: &git2::Repository) -> Result<Vec<TodoItem>, Error> {
+    let key = match cache::head_key(repository) {
+        Some(k) => k,
+        None => return Ok(Vec::new()),
+    };
+    if let Some(todos) = cache::read(cache_dir, repository, CACHE_NAME, &key) {
+        return Ok(todos);
+    }
+
+    let pattern = Regex::new(
+        r"(?://+|#+|/\*+|\*|--|<!--|;+)\s*(TODO|FIXME|HACK|XXX)\b(?:\([^)]*\))?:?\s*(.*)",
+    )
+    .unwrap();
+    let index = repository.index()?;
+    let mut todos = Vec::new();
+    for entry in index.iter() {
+        if entry.mode & 0o170000 != 0o100000 || entry.flags & INDEX_STAGE_MASK != 0 {
+            continue;
+        }
+        let blob = match repository.find_blob(entry.id) {
+            Ok(b) => b,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if blob.is_binary() || blob.size() > MAX_FILE_BYTES {
+            continue;
+        }
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let content = String::from_utf8_lossy(blob.content());
+        let mut found: Vec<(usize, TodoKind, String)> = Vec::new();
+        for (n, line) in content.lines().enumerate() {
+            if let Some(captures) = pattern.captures(line) {
+                let kind = match &captures[1] {
+                    "TODO" => TodoKind::Todo,
+                    "FIXME" => TodoKind::Fixme,
+                    "HACK" => TodoKind::Hack,
+                    _ => TodoKind::Xxx,
+                };
+                let text = captures[2]
+                    .trim()
+                    .trim_end_matches("*/")
+                    .trim_end_matches("-->")
+                    .trim()
+                    .chars()
+                    .take(MAX_TEXT_CHARS)
+                    .collect();
+                found.push((n + 1, kind, text));
+            }
+        }
+        if found.is_empty() {
+            continue;
+        }
+
+        let blame = repository
+            .blame_file(Path::new(&path), None)
+            .map_err(|e| log::warn!("[list_todos] {}: {:?}", path, e))
+            .ok();
+        let blame = blame.as_ref().and_then(|b| {
+            b.blame_buffer(blob.content())
+                .map_err(|e| log::warn!("[list_todos] {}: {:?}", path, e))
+                .ok()
+        });
+        for (line, kind, text) in found {
+            let hunk = blame
+                .as_ref()
+                .and_then(|b| b.get_line(line))
+                .filter(|h| !h.final_commit_id().is_zero());
+            let signature = hunk.as_ref().map(|h| h.final_signature());
+            todos.push(TodoItem {
+                kind,
+                path: path.clone(),
+                line,
+                text,
+                author: signature.as_ref().and_then(|s| s.name().map(String::from)),
+                email: signature.as_ref().and_then(|s| s.email().map(String::from)),
+                date: signature
+                    .as_ref()
+                    .and_then(|s| Utc.timestamp_opt(s.when().seconds(), 0).single()),
+                commit: hunk.as_ref().map(|h| h.final_commit_id().to_string()),
+            });
+        }
+    }
+
+    if let Err(e) = cache::write(cache_dir, repository, CACHE_NAME, &key, &todos) {
+        log::warn!("[list_todos] {:?}", e);
+    }
+    Ok(todos)
+}
+
+pub fn filter_todos(todos: Vec<TodoItem>, filter: &TodoFilter) -> Vec<TodoItem> {
+    let cutoff = filter
+        .min_age_days
+        .map(|days| Utc::now() - chrono::Duration::days(days));
+    let author = filter.author.as_ref().map(|a| a.to_lowercase());
+    todos
+        .into_iter()
+        .filter(|t| filter.kinds.is_empty() || filter.kinds.contains(&t.kind))
+        .filter(|t| match &author {
+            Some(author) => [&t.author, &t.email].iter().any(|v| {
+                v.as_ref()
+                    .map(|v| v.to_lowercase().contains(author))
+                    .unwrap_or(false)
+            }),
+            None => true,
+        })
+        .filter(|t| match &filter.path {
+            Some(path) => t.path.contains(path.as_str()),
+            None => true,
+        })
+        .filter(|t| match (cutoff, t.date) {
+            (Some(cutoff), Some(date)) => date <= cutoff,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect()
+}
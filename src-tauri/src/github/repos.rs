@@ -119,3 +119,153 @@ pub async fn is_remote_branch(
     };
     Ok(branches.into_iter().any(|b| b.name == branch))
 }
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CreateParameters {
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: Visibility,
+    pub org: Option<String>,
+}
+
+pub async fn create_repository(
+    token: &str,
+    params: &CreateParameters,
+) -> Result<Repository, Error> {
+    let octo = octocrab::OctocrabBuilder::default()
+        .personal_token(token.into())
+        .build()?;
+    let route = match &params.org {
+        Some(org) => format!("/orgs/{}/repos", org),
+        None => String::from("/user/repos"),
+    };
+    let body = serde_json::json!({
+        "name": params.name,
+        "description": params.description,
+        "private": matches!(params.visibility, Visibility::Private),
+    });
+    let repo: octocrab::models::Repository = match octo.post(route, Some(&body)).await {
+        Ok(r) => r,
+        Err(e) => {
+            log::info!("[create_repository] name: {}", params.name);
+            return Err(Error::OctocrabError(e));
+        }
+    };
+    Ok(Repository::from(&repo))
+}
+
+pub async fn find_repository(
+    token: &str,
+    params: &CreateParameters,
+) -> Result<Option<Repository>, Error> {
+    let octo = octocrab::OctocrabBuilder::default()
+        .personal_token(token.into())
+        .build()?;
+    let owner = match &params.org {
+        Some(org) => org.clone(),
+        None => octo.current().user().await?.login,
+    };
+    match octo.repos(owner.clone(), params.name.clone()).get().await {
+        Ok(r) => Ok(Some(Repository::from(&r))),
+        Err(octocrab::Error::GitHub { source, .. }) if source.message == "Not Found" => Ok(None),
+        Err(e) => {
+            log::info!("[find_repository] repo: {}/{}", owner, params.name);
+            Err(Error::OctocrabError(e))
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RemoteTag {
+    pub name: String,
+    pub sha: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub published_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+}
+
+pub async fn list_tags(token: &str, repository: &Repository) -> Result<Vec<RemoteTag>, Error> {
+    let owner = match repository.clone().owner {
+        Some(o) => o,
+        None => return Err(Error::NoOwnerError),
+    };
+    let repo_name = repository.clone().name;
+    let octo = octocrab::OctocrabBuilder::default()
+        .personal_token(token.into())
+        .build()?;
+    let mut page = 1u32;
+    let mut number_pages = 1u32;
+    let mut tags = Vec::new();
+    while page <= number_pages {
+        let repo = octo.repos(owner.clone(), repo_name.clone());
+        let tags_page = match repo.list_tags().per_page(100).page(page).send().await {
+            Ok(t) => t,
+            Err(e) => {
+                log::info!("[list_tags] repo: {}/{}, page: {}", owner, repo_name, page);
+                return Err(Error::OctocrabError(e));
+            }
+        };
+        number_pages = tags_page.number_of_pages().unwrap_or(1);
+        tags.extend(tags_page.into_iter().map(|t| RemoteTag {
+            name: t.name,
+            sha: t.commit.sha,
+        }));
+
+        page += 1;
+    }
+
+    Ok(tags)
+}
+
+pub async fn list_releases(token: &str, repository: &Repository) -> Result<Vec<Release>, Error> {
+    let owner = match repository.clone().owner {
+        Some(o) => o,
+        None => return Err(Error::NoOwnerError),
+    };
+    let repo_name = repository.clone().name;
+    let octo = octocrab::OctocrabBuilder::default()
+        .personal_token(token.into())
+        .build()?;
+    let mut page = 1u32;
+    let mut number_pages = 1u32;
+    let mut releases = Vec::new();
+    while page <= number_pages {
+        let repo = octo.repos(owner.clone(), repo_name.clone());
+        let releases_page = match repo.releases().list().per_page(100).page(page).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                log::info!(
+                    "[list_releases] repo: {}/{}, page: {}",
+                    owner,
+                    repo_name,
+                    page
+                );
+                return Err(Error::OctocrabError(e));
+            }
+        };
+        number_pages = releases_page.number_of_pages().unwrap_or(1);
+        releases.extend(releases_page.into_iter().map(|r| Release {
+            tag_name: r.tag_name,
+            name: r.name,
+            draft: r.draft,
+            prerelease: r.prerelease,
+            published_at: r.published_at,
+        }));
+
+        page += 1;
+    }
+
+    Ok(releases)
+}
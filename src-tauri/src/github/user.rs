@@ -26,9 +26,41 @@ pub struct Repository {
     pub description: Option<String>,
     pub ssh_url: Option<String>,
     pub visibility: Option<String>,
+    pub license: Option<String>,
     pub created_at: Option<chrono::DateTime<chrono::offset::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::offset::Utc>>,
 }
+impl From<&octocrab::models::Repository> for Repository {
+    fn from(repo: &octocrab::models::Repository) -> Self {
+        let id = repo.id.0 as u32;
+        let name = repo.name.clone();
+        let url = repo
+            .full_name
+            .clone()
+            .map(|name| String::from("github.com/") + name.as_str());
+        let owner = repo.owner.clone().map(|owner| owner.login);
+        let description = repo.description.clone();
+        let ssh_url = repo.ssh_url.clone();
+        let visibility = repo.visibility.clone();
+        let license = repo.license.clone().map(|license| license.spdx_id);
+        let created_at = repo.created_at;
+        let updated_at = repo.updated_at;
+
+        Repository {
+            id,
+            name,
+            url,
+            owner,
+            description,
+            ssh_url,
+            visibility,
+            license,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
 pub async fn list_repos(token: &str, params: &ListParameters) -> Result<Vec<Repository>, Error> {
     let octo = octocrab::OctocrabBuilder::default()
         .personal_token(token.into())
@@ -66,32 +98,7 @@ pub async fn list_repos(token: &str, params: &ListParameters) -> Result<Vec<Repo
     let repos = page
         .items
         .iter()
-        .map(|repo| {
-            let id = repo.id.0 as u32;
-            let name = repo.name.clone();
-            let url = repo
-                .full_name
-                .clone()
-                .and_then(|name| Some(String::from("github.com/") + name.as_str()));
-            let owner = repo.owner.clone().and_then(|owner| Some(owner.login));
-            let description = repo.description.clone();
-            let ssh_url = repo.ssh_url.clone();
-            let visibility = repo.visibility.clone();
-            let created_at = repo.created_at;
-            let updated_at = repo.updated_at;
-
-            Repository {
-                id,
-                name,
-                url,
-                owner,
-                description,
-                ssh_url,
-                visibility,
-                created_at,
-                updated_at,
-            }
-        })
+        .map(Repository::from)
         .collect::<Vec<Repository>>();
 
     Ok(repos)
@@ -0,0 +1,378 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::{
+    lockfile::{self, Ecosystem, LockedPackage},
+    manifest::{read_json, read_toml},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum LicenseStatus {
+    Allowed,
+    Denied,
+    Unlisted,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct LicensePolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LicenseSource {
+    pub source: String,
+    pub license: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectLicense {
+    pub sources: Vec<LicenseSource>,
+    pub licenses: Vec<String>,
+    pub github: Option<String>,
+    pub consistent: bool,
+    pub matches_github: Option<bool>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DependencyLicense {
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub status: LicenseStatus,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LicenseReport {
+    pub project: ProjectLicense,
+    pub status: LicenseStatus,
+    pub dependencies: Vec<DependencyLicense>,
+    pub denied: usize,
+    pub unlisted: usize,
+    pub unknown: usize,
+}
+
+const LICENSE_FILE_PREFIXES: [&str; 4] = ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+const CARGO_MANIFESTS: [&str; 2] = ["Cargo.toml", "src-tauri/Cargo.toml"];
+const NO_ASSERTION: &str = "NOASSERTION";
+
+const LICENSE_TEXTS: [(&str, &[&str]); 15] = [
+    ("AGPL-3.0", &["gnu affero general public license"]),
+    (
+        "LGPL-3.0",
+        &["gnu lesser general public license", "version 3"],
+    ),
+    (
+        "LGPL-2.1",
+        &["gnu lesser general public license", "version 2.1"],
+    ),
+    ("GPL-3.0", &["gnu general public license", "version 3"]),
+    ("GPL-2.0", &["gnu general public license", "version 2"]),
+    ("MPL-2.0", &["mozilla public license", "2.0"]),
+    ("Apache-2.0", &["apache license", "version 2.0"]),
+    ("BSL-1.0", &["boost software license"]),
+    ("Unlicense", &["this is free and unencumbered software"]),
+    ("CC0-1.0", &["cc0 1.0"]),
+    (
+        "Zlib",
+        &[
+            "provided 'as-is'",
+            "altered source versions must be plainly marked",
+        ],
+    ),
+    (
+        "ISC",
+        &["permission to use, copy, modify, and/or distribute"],
+    ),
+    (
+        "BSD-3-Clause",
+        &[
+            "redistribution and use in source and binary forms",
+            "neither the name",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["redistribution and use in source and binary forms"],
+    ),
+    ("MIT", &["permission is hereby granted, free of charge"]),
+];
+
+impl ProjectLicense {
+    pub fn primary(&self) -> Option<String> {
+        self.sources
+            .iter()
+            .filter(|s| !is_license_file(&s.source))
+            .find_map(|s| s.license.clone())
+            .or_else(|| match self.licenses.is_empty() {
+                true => None,
+                false => Some(self.licenses.join(" AND ")),
+            })
+    }
+}
+
+pub fn report(path: &Path, github: Option<&str>, policy: &LicensePolicy) -> LicenseReport {
+    let project = project_license(path, github);
+    let status = evaluate(project.primary().as_deref(), policy);
+    let dependencies = dependency_licenses(path, policy);
+    let count = |status: LicenseStatus| dependencies.iter().filter(|d| d.status == status).count();
+    LicenseReport {
+        denied: count(LicenseStatus::Denied),
+        unlisted: count(LicenseStatus::Unlisted),
+        unknown: count(LicenseStatus::Unknown),
+        project,
+        status,
+        dependencies,
+    }
+}
+
+pub fn project_license(path: &Path, github: Option<&str>) -> ProjectLicense {
+    let mut sources = Vec::new();
+    for manifest in CARGO_MANIFESTS {
+        if let Some(package) =
+            read_toml(&path.join(manifest)).and_then(|d| d.get("package").cloned())
+        {
+            sources.push(LicenseSource {
+                source: manifest.to_string(),
+                license: package
+                    .get("license")
+                    .and_then(|l| l.as_str())
+                    .map(String::from),
+            });
+        }
+    }
+    if let Some(json) = read_json(&path.join("package.json")) {
+        sources.push(LicenseSource {
+            source: String::from("package.json"),
+            license: npm_license(&json),
+        });
+    }
+    let mut files = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| {
+                LICENSE_FILE_PREFIXES
+                    .iter()
+                    .any(|p| n.to_uppercase().starts_with(p))
+            })
+            .collect::<Vec<String>>(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    for file in files {
+        sources.push(LicenseSource {
+            license: detect_text(&path.join(&file)),
+            source: file,
+        });
+    }
+
+    let mut licenses: Vec<String> = Vec::new();
+    for license in sources.iter().filter_map(|s| s.license.clone()) {
+        if !licenses.contains(&license) {
+            licenses.push(license);
+        }
+    }
+    let manifest_ids = sources
+        .iter()
+        .filter(|s| !is_license_file(&s.source))
+        .filter_map(|s| s.license.as_ref())
+        .map(|l| license_ids(l))
+        .collect::<Vec<HashSet<String>>>();
+    let file_ids = sources
+        .iter()
+        .filter(|s| is_license_file(&s.source))
+        .filter_map(|s| s.license.as_ref())
+        .flat_map(|l| license_ids(l))
+        .collect::<HashSet<String>>();
+    let consistent = manifest_ids.windows(2).all(|w| w[0] == w[1])
+        && manifest_ids
+            .first()
+            .map(|ids| file_ids.is_subset(ids))
+            .unwrap_or(true);
+
+    let github = github
+        .filter(|g| !g.eq_ignore_ascii_case(NO_ASSERTION))
+        .map(String::from);
+    let all_ids = licenses
+        .iter()
+        .flat_map(|l| license_ids(l))
+        .collect::<HashSet<String>>();
+    let matches_github = match &github {
+        Some(g) if !all_ids.is_empty() => Some(all_ids.contains(&g.to_lowercase())),
+        _ => None,
+    };
+
+    ProjectLicense {
+        sources,
+        licenses,
+        github,
+        consistent,
+        matches_github,
+    }
+}
+
+pub fn dependency_licenses(path: &Path, policy: &LicensePolicy) -> Vec<DependencyLicense> {
+    let mut seen = HashSet::new();
+    let mut dependencies = Vec::new();
+    for lockfile in lockfile::lockfiles(path) {
+        for package in lockfile.packages.iter() {
+            if !seen.insert((
+                lockfile.ecosystem,
+                package.name.clone(),
+                package.version.clone(),
+            )) {
+                continue;
+            }
+            if lockfile.ecosystem == Ecosystem::Cargo && !package.is_registry() {
+                continue;
+            }
+            let license = package_license(path, lockfile.ecosystem, package);
+            dependencies.push(DependencyLicense {
+                ecosystem: lockfile.ecosystem,
+                package: package.name.clone(),
+                version: package.version.clone(),
+                status: evaluate(license.as_deref(), policy),
+                license,
+            });
+        }
+    }
+    dependencies.sort_by(|a, b| a.package.cmp(&b.package).then(a.version.cmp(&b.version)));
+    dependencies
+}
+
+pub fn package_license(
+    path: &Path,
+    ecosystem: Ecosystem,
+    package: &LockedPackage,
+) -> Option<String> {
+    match ecosystem {
+        Ecosystem::Cargo => cargo_license(package),
+        Ecosystem::Npm => package.license.clone().or_else(|| {
+            read_json(
+                &path
+                    .join("node_modules")
+                    .join(&package.name)
+                    .join("package.json"),
+            )
+            .and_then(|json| npm_license(&json))
+        }),
+    }
+}
+
+pub fn evaluate(expression: Option<&str>, policy: &LicensePolicy) -> LicenseStatus {
+    let expression = match expression {
+        Some(e) if !e.trim().is_empty() => e,
+        _ => return LicenseStatus::Unknown,
+    };
+    let allow = policy
+        .allow
+        .iter()
+        .map(|l| l.to_lowercase())
+        .collect::<HashSet<String>>();
+    let deny = policy
+        .deny
+        .iter()
+        .map(|l| l.to_lowercase())
+        .collect::<HashSet<String>>();
+    let statuses = alternatives(expression)
+        .iter()
+        .map(|ids| {
+            if ids.iter().any(|id| deny.contains(id)) {
+                LicenseStatus::Denied
+            } else if allow.is_empty() || ids.iter().all(|id| allow.contains(id)) {
+                LicenseStatus::Allowed
+            } else {
+                LicenseStatus::Unlisted
+            }
+        })
+        .collect::<Vec<LicenseStatus>>();
+    if statuses.contains(&LicenseStatus::Allowed) {
+        LicenseStatus::Allowed
+    } else if !statuses.is_empty() && statuses.iter().all(|s| *s == LicenseStatus::Denied) {
+        LicenseStatus::Denied
+    } else {
+        LicenseStatus::Unlisted
+    }
+}
+
+fn alternatives(expression: &str) -> Vec<Vec<String>> {
+    let expression = expression
+        .replace(['(', ')'], " ")
+        .replace('/', " OR ")
+        .to_lowercase();
+    expression
+        .split(" or ")
+        .map(|alternative| {
+            alternative
+                .split(" and ")
+                .filter_map(|id| id.split(" with ").next())
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<String>>()
+        })
+        .filter(|ids| !ids.is_empty())
+        .collect()
+}
+
+fn license_ids(expression: &str) -> HashSet<String> {
+    alternatives(expression).into_iter().flatten().collect()
+}
+
+fn is_license_file(source: &str) -> bool {
+    !source.ends_with(".toml") && !source.ends_with(".json")
+}
+
+fn npm_license(json: &serde_json::Value) -> Option<String> {
+    match &json["license"] {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Object(o) => o.get("type").and_then(|t| t.as_str()).map(String::from),
+        _ => {
+            let licenses = json["licenses"]
+                .as_array()?
+                .iter()
+                .filter_map(|l| l["type"].as_str())
+                .collect::<Vec<&str>>();
+            match licenses.is_empty() {
+                true => None,
+                false => Some(licenses.join(" OR ")),
+            }
+        }
+    }
+}
+
+fn cargo_license(package: &LockedPackage) -> Option<String> {
+    let registry = lockfile::cargo_home()?.join("registry").join("src");
+    let dir_name = format!("{}-{}", package.name, package.version);
+    for index in fs::read_dir(registry).ok()?.flatten() {
+        let dir = index.path().join(&dir_name);
+        let document = match read_toml(&dir.join("Cargo.toml")) {
+            Some(d) => d,
+            None => continue,
+        };
+        let package = document.get("package")?;
+        if let Some(license) = package.get("license").and_then(|l| l.as_str()) {
+            return Some(license.to_string());
+        }
+        return package
+            .get("license-file")
+            .and_then(|f| f.as_str())
+            .and_then(|f| detect_text(&dir.join(f)));
+    }
+    None
+}
+
+fn detect_text(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let content = content
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase();
+    LICENSE_TEXTS
+        .iter()
+        .find(|(_, phrases)| phrases.iter().all(|p| content.contains(p)))
+        .map(|(id, _)| id.to_string())
+}
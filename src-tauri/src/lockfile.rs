@@ -0,0 +1,435 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::manifest::read_json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct LockedDependency {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+    pub checksum: Option<String>,
+    pub license: Option<String>,
+    pub dependencies: Vec<LockedDependency>,
+}
+
+impl LockedPackage {
+    pub fn is_registry(&self) -> bool {
+        self.source
+            .as_ref()
+            .map(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Lockfile {
+    pub ecosystem: Ecosystem,
+    pub path: String,
+    pub packages: Vec<LockedPackage>,
+    pub roots: Vec<LockedDependency>,
+}
+
+struct YarnEntry {
+    package: LockedPackage,
+    specs: Vec<String>,
+    requires: Vec<(String, String)>,
+}
+
+const CARGO_LOCKFILES: [&str; 2] = ["Cargo.lock", "src-tauri/Cargo.lock"];
+const NPM_LOCKFILES: [&str; 1] = ["package-lock.json"];
+const YARN_LOCKFILE: &str = "yarn.lock";
+const NPM_TABLES: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "optionalDependencies",
+    "peerDependencies",
+];
+
+pub fn lockfiles(path: &Path) -> Vec<Lockfile> {
+    let mut lockfiles = Vec::new();
+    for lockfile in CARGO_LOCKFILES {
+        if let Some((packages, roots)) = cargo_packages(&path.join(lockfile)) {
+            lockfiles.push(Lockfile {
+                ecosystem: Ecosystem::Cargo,
+                path: lockfile.to_string(),
+                packages,
+                roots,
+            });
+        }
+    }
+    for lockfile in NPM_LOCKFILES {
+        if let Some((packages, roots)) = npm_packages(&path.join(lockfile)) {
+            lockfiles.push(Lockfile {
+                ecosystem: Ecosystem::Npm,
+                path: lockfile.to_string(),
+                packages,
+                roots,
+            });
+        }
+    }
+    if let Some((packages, roots)) = yarn_packages(&path.join(YARN_LOCKFILE)) {
+        lockfiles.push(Lockfile {
+            ecosystem: Ecosystem::Npm,
+            path: YARN_LOCKFILE.to_string(),
+            packages,
+            roots,
+        });
+    }
+    lockfiles
+}
+
+pub fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|h| PathBuf::from(h).join(".cargo"))
+        })
+}
+
+fn cargo_packages(path: &Path) -> Option<(Vec<LockedPackage>, Vec<LockedDependency>)> {
+    let content = fs::read_to_string(path).ok()?;
+    let document = content
+        .parse::<toml_edit::Document>()
+        .map_err(|e| log::warn!("{:?}: {:?}", path, e))
+        .ok()?;
+    let string = |item: Option<&toml_edit::Item>| item.and_then(|i| i.as_str()).map(String::from);
+    let mut packages = Vec::new();
+    let mut requires = Vec::new();
+    for entry in document.get("package")?.as_array_of_tables()?.iter() {
+        let (name, version) = match (string(entry.get("name")), string(entry.get("version"))) {
+            (Some(n), Some(v)) => (n, v),
+            _ => continue,
+        };
+        packages.push(LockedPackage {
+            name,
+            version,
+            source: string(entry.get("source")),
+            checksum: string(entry.get("checksum")),
+            license: None,
+            dependencies: Vec::new(),
+        });
+        requires.push(
+            entry
+                .get("dependencies")
+                .and_then(|d| d.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(String::from)
+                        .collect::<Vec<String>>()
+                })
+                .unwrap_or_default(),
+        );
+    }
+
+    let resolved = requires
+        .iter()
+        .map(|specs| {
+            specs
+                .iter()
+                .filter_map(|spec| {
+                    let mut parts = spec.split_whitespace();
+                    let name = parts.next()?;
+                    let version = parts.next();
+                    packages
+                        .iter()
+                        .find(|p| p.name == name && version.map(|v| v == p.version).unwrap_or(true))
+                        .map(|p| LockedDependency {
+                            name: p.name.clone(),
+                            version: p.version.clone(),
+                        })
+                })
+                .collect::<Vec<LockedDependency>>()
+        })
+        .collect::<Vec<Vec<LockedDependency>>>();
+    for (package, dependencies) in packages.iter_mut().zip(resolved) {
+        package.dependencies = dependencies;
+    }
+
+    let is_local = |dependency: &LockedDependency| {
+        packages.iter().any(|p| {
+            p.source.is_none() && p.name == dependency.name && p.version == dependency.version
+        })
+    };
+    let mut roots: Vec<LockedDependency> = Vec::new();
+    for package in packages.iter().filter(|p| p.source.is_none()) {
+        for dependency in package.dependencies.iter() {
+            if !is_local(dependency) && !roots.contains(dependency) {
+                roots.push(dependency.clone());
+            }
+        }
+    }
+    Some((packages, roots))
+}
+
+fn npm_packages(path: &Path) -> Option<(Vec<LockedPackage>, Vec<LockedDependency>)> {
+    let json = read_json(path)?;
+    let mut packages = Vec::new();
+    let mut roots: Vec<LockedDependency> = Vec::new();
+    if let Some(entries) = json["packages"].as_object() {
+        let resolve = |from: &str, dependency: &str| {
+            let mut base = from.to_string();
+            loop {
+                let key = match base.is_empty() {
+                    true => format!("node_modules/{}", dependency),
+                    false => format!("{}/node_modules/{}", base, dependency),
+                };
+                if let Some(entry) = entries.get(&key) {
+                    if entry["link"].as_bool().unwrap_or(false) {
+                        return None;
+                    }
+                    return Some(LockedDependency {
+                        name: entry["name"].as_str().unwrap_or(dependency).to_string(),
+                        version: entry["version"].as_str()?.to_string(),
+                    });
+                }
+                if base.is_empty() {
+                    return None;
+                }
+                base = match base.rfind("/node_modules/") {
+                    Some(i) => base[..i].to_string(),
+                    None => String::new(),
+                };
+            }
+        };
+        let requires = |key: &str, entry: &serde_json::Value| {
+            NPM_TABLES
+                .iter()
+                .filter_map(|t| entry[*t].as_object())
+                .flat_map(|o| o.keys())
+                .filter_map(|dependency| resolve(key, dependency))
+                .collect::<Vec<LockedDependency>>()
+        };
+        for (key, entry) in entries.iter() {
+            let name = match key.rsplit_once("node_modules/") {
+                Some((_, n)) => n,
+                None => {
+                    for dependency in requires(key, entry) {
+                        if !roots.contains(&dependency) {
+                            roots.push(dependency);
+                        }
+                    }
+                    continue;
+                }
+            };
+            if entry["link"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            if let Some(version) = entry["version"].as_str() {
+                packages.push(LockedPackage {
+                    name: entry["name"].as_str().unwrap_or(name).to_string(),
+                    version: version.to_string(),
+                    source: entry["resolved"].as_str().map(String::from),
+                    checksum: entry["integrity"].as_str().map(String::from),
+                    license: entry["license"].as_str().map(String::from),
+                    dependencies: requires(key, entry),
+                });
+            }
+        }
+        return Some((packages, roots));
+    }
+
+    npm_v1_packages(&json["dependencies"], &mut Vec::new(), &mut packages);
+    if let Some(manifest) = read_json(&path.with_file_name("package.json")) {
+        for dependency in NPM_TABLES
+            .iter()
+            .filter_map(|t| manifest[*t].as_object())
+            .flat_map(|o| o.keys())
+        {
+            if let Some(version) = json["dependencies"][dependency.as_str()]["version"].as_str() {
+                roots.push(LockedDependency {
+                    name: dependency.clone(),
+                    version: version.to_string(),
+                });
+            }
+        }
+    }
+    Some((packages, roots))
+}
+
+fn npm_v1_packages<'a>(
+    dependencies: &'a serde_json::Value,
+    scopes: &mut Vec<&'a serde_json::Value>,
+    packages: &mut Vec<LockedPackage>,
+) {
+    scopes.push(dependencies);
+    for (name, entry) in dependencies.as_object().into_iter().flatten() {
+        let nested = &entry["dependencies"];
+        let requires = entry["requires"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(dependency, _)| {
+                std::iter::once(nested)
+                    .chain(scopes.iter().rev().copied())
+                    .find_map(|scope| scope[dependency.as_str()]["version"].as_str())
+                    .map(|version| LockedDependency {
+                        name: dependency.clone(),
+                        version: version.to_string(),
+                    })
+            })
+            .collect();
+        if let Some(version) = entry["version"].as_str() {
+            packages.push(LockedPackage {
+                name: name.clone(),
+                version: version.to_string(),
+                source: entry["resolved"].as_str().map(String::from),
+                checksum: entry["integrity"].as_str().map(String::from),
+                license: None,
+                dependencies: requires,
+            });
+        }
+        npm_v1_packages(nested, scopes, packages);
+    }
+    scopes.pop();
+}
+
+fn yarn_packages(path: &Path) -> Option<(Vec<LockedPackage>, Vec<LockedDependency>)> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut entries: Vec<YarnEntry> = Vec::new();
+    let mut current: Option<YarnEntry> = None;
+    let mut in_dependencies = false;
+    for line in content.lines().chain(std::iter::once("")) {
+        if line.starts_with(|c: char| !c.is_whitespace()) || line.is_empty() {
+            if let Some(entry) = current.take() {
+                let version = &entry.package.version;
+                if !version.is_empty() && !version.contains("use.local") {
+                    entries.push(entry);
+                }
+            }
+            in_dependencies = false;
+            if line.starts_with('#') || line.starts_with("__metadata") || line.is_empty() {
+                continue;
+            }
+            let specs = line
+                .trim_end_matches(':')
+                .split(", ")
+                .map(|s| s.trim_matches('"').to_string())
+                .collect::<Vec<String>>();
+            let spec = specs.first().cloned().unwrap_or_default();
+            let name = match spec.char_indices().skip(1).find(|(_, c)| *c == '@') {
+                Some((i, _)) => &spec[..i],
+                None => &spec,
+            };
+            current = Some(YarnEntry {
+                package: LockedPackage {
+                    name: name.to_string(),
+                    version: String::new(),
+                    source: None,
+                    checksum: None,
+                    license: None,
+                    dependencies: Vec::new(),
+                },
+                specs,
+                requires: Vec::new(),
+            });
+            continue;
+        }
+        let entry = match current.as_mut() {
+            Some(e) => e,
+            None => continue,
+        };
+        let indent = line.len() - line.trim_start().len();
+        if indent > 2 {
+            if in_dependencies {
+                entry.requires.extend(yarn_field(line));
+            }
+            continue;
+        }
+        let (key, value) = match yarn_field(line) {
+            Some(field) => field,
+            None => continue,
+        };
+        in_dependencies = key == "dependencies" || key == "optionalDependencies";
+        let package = &mut entry.package;
+        match key.as_str() {
+            "version" => package.version = value,
+            "resolved" | "resolution" => package.source = Some(value),
+            "integrity" | "checksum" => package.checksum = Some(value),
+            _ => (),
+        }
+    }
+
+    let resolve = |name: &str, range: &str| {
+        let specs = [
+            format!("{}@{}", name, range),
+            format!("{}@npm:{}", name, range),
+        ];
+        let candidates = entries
+            .iter()
+            .filter(|e| e.package.name == name)
+            .collect::<Vec<&YarnEntry>>();
+        candidates
+            .iter()
+            .find(|e| e.specs.iter().any(|s| specs.contains(s)))
+            .or_else(|| match candidates.len() {
+                1 => candidates.first(),
+                _ => None,
+            })
+            .map(|e| LockedDependency {
+                name: e.package.name.clone(),
+                version: e.package.version.clone(),
+            })
+    };
+    let mut packages: Vec<LockedPackage> = Vec::new();
+    for entry in entries.iter() {
+        let known = packages
+            .iter()
+            .any(|p| p.name == entry.package.name && p.version == entry.package.version);
+        if known {
+            continue;
+        }
+        let mut package = entry.package.clone();
+        package.dependencies = entry
+            .requires
+            .iter()
+            .filter_map(|(name, range)| resolve(name, range))
+            .collect();
+        packages.push(package);
+    }
+
+    let mut roots: Vec<LockedDependency> = Vec::new();
+    if let Some(manifest) = read_json(&path.with_file_name("package.json")) {
+        for (name, range) in NPM_TABLES
+            .iter()
+            .filter_map(|t| manifest[*t].as_object())
+            .flatten()
+        {
+            if let Some(dependency) = resolve(name, range.as_str().unwrap_or_default()) {
+                if !roots.contains(&dependency) {
+                    roots.push(dependency);
+                }
+            }
+        }
+    }
+    Some((packages, roots))
+}
+
+fn yarn_field(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    let (key, value) = match line.strip_prefix('"') {
+        Some(rest) => {
+            let (key, value) = rest.split_once('"')?;
+            (key, value.trim_start_matches(':'))
+        }
+        None => line.split_once([' ', ':'])?,
+    };
+    Some((key.to_string(), value.trim().trim_matches('"').to_string()))
+}
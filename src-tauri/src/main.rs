@@ -2,24 +2,48 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::{
+    cmp::Reverse,
     collections::HashMap,
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    fs,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, atomic::Ordering, Arc, Mutex},
 };
 
+use git::{
+    branch, cleanup, clone, commit, diff, graph, history, languages, maintenance, remote, secrets,
+    stage, stash, sync, tag, todos,
+};
 use github::repos;
-use project::{Project, Projects};
-use tauri::async_runtime::block_on;
+use manifest::{ManifestSummary, ProjectKind};
+use project::{Project, Projects, RemoteUrlType};
+use release::{changelog, version};
+use tauri::{async_runtime::block_on, Manager};
 use uuid::Uuid;
 
 use crate::github::user::ListParameters;
 
+mod audit;
+mod bulk;
+mod dependencies;
+mod disk;
+mod git;
 mod github;
+mod licenses;
+mod lockfile;
+mod manifest;
 mod project;
+mod release;
+mod sbom;
+mod search;
 
 struct TokenState(Arc<Mutex<Option<String>>>);
 struct ProjectDirState(Arc<Mutex<Vec<PathBuf>>>);
 struct ProjectsState(Arc<Mutex<Projects>>);
+struct BulkState(Arc<Mutex<HashMap<Uuid, Arc<AtomicBool>>>>);
+struct SecretRulesState(Arc<Mutex<Vec<secrets::SecretRule>>>);
+struct EnvState(PathBuf);
+struct AdvisoryState(Arc<Mutex<audit::AdvisorySources>>);
+struct LicensePolicyState(Arc<Mutex<licenses::LicensePolicy>>);
 
 #[derive(Debug, thiserror::Error, serde::Serialize, serde::Deserialize)]
 pub enum Error {
@@ -41,10 +65,335 @@ pub enum Error {
     RemoteCommitsError,
     #[error("Error occured while getting changes")]
     ChangesError,
+    #[error("Project has no remote repository")]
+    NoRemoteError,
+    #[error("Project already has a local repository")]
+    LocalExistsError,
+    #[error("Error occured while cloning repository")]
+    CloneError,
+    #[error("Project has no local repository")]
+    NoLocalError,
+    #[error("Project is not a git repository")]
+    NoGitError,
+    #[error("Error occured while accessing git repository")]
+    GitError,
+    #[error("Project is already linked to a remote repository")]
+    RemoteExistsError,
+    #[error("Error occured while publishing repository")]
+    PublishError,
+    #[error("Worktree has uncommitted changes")]
+    DirtyWorktreeError,
+    #[error("Branch has diverged from its upstream")]
+    DivergedError,
+    #[error("Branch has no upstream")]
+    NoUpstreamError,
+    #[error("Error occured while synchronizing with remote")]
+    SyncError,
+    #[error("Checkout would overwrite local changes")]
+    WouldClobberError,
+    #[error("Branch is not merged")]
+    NotMergedError,
+    #[error("Branch is checked out")]
+    CheckedOutError,
+    #[error("Error occured while managing branch")]
+    BranchError,
+    #[error("Error occured while building cleanup report")]
+    CleanupError,
+    #[error("No stash exists at the given index")]
+    NoStashError,
+    #[error("Error occured while managing stash")]
+    StashError,
+    #[error("Error occured while computing diff")]
+    DiffError,
+    #[error("Error occured while staging changes")]
+    StageError,
+    #[error("Nothing staged to commit")]
+    NothingToCommitError,
+    #[error("Commit message is empty")]
+    EmptyMessageError,
+    #[error("Error occured while committing")]
+    CommitError,
+    #[error("Error occured while reading commit history")]
+    LogError,
+    #[error("Error occured while building commit graph")]
+    GraphError,
+    #[error("Error occured while managing tags")]
+    TagError,
+    #[error("Error occured while listing remote tags")]
+    RemoteTagsError,
+    #[error("Error occured while generating changelog")]
+    ChangelogError,
+    #[error("Error occured while measuring disk usage")]
+    DiskUsageError,
+    #[error("Error occured while running maintenance")]
+    MaintenanceError,
+    #[error("Invalid secret rule")]
+    InvalidRuleError,
+    #[error("Error occured while scanning for secrets")]
+    SecretScanError,
+    #[error("Error occured while searching")]
+    SearchError,
+    #[error("Error occured while bumping version")]
+    VersionError,
+    #[error("No manifest with a version found")]
+    NoManifestError,
+    #[error("Nothing to release")]
+    NothingToReleaseError,
+    #[error("Error occured while loading advisory database")]
+    AdvisoryDatabaseError,
+    #[error("Error occured while generating SBOM")]
+    SbomError,
+    #[error("Could not resolve app data directory")]
+    NoAppDataError,
+    #[error("Error occured while saving settings")]
+    SettingsError,
+    #[error("Projects must be selected unless running dry")]
+    NoProjectsSelectedError,
+}
+
+impl From<tag::Error> for Error {
+    fn from(e: tag::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            tag::Error::EmptyMessage => Error::EmptyMessageError,
+            _ => Error::TagError,
+        }
+    }
+}
+
+impl From<stash::Error> for Error {
+    fn from(e: stash::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            stash::Error::NoStash(_) => Error::NoStashError,
+            stash::Error::WouldClobber(_) => Error::WouldClobberError,
+            _ => Error::StashError,
+        }
+    }
+}
+
+impl From<sync::Error> for Error {
+    fn from(e: sync::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            sync::Error::DirtyWorktree => Error::DirtyWorktreeError,
+            sync::Error::Diverged(_) => Error::DivergedError,
+            sync::Error::NoUpstream(_) => Error::NoUpstreamError,
+            _ => Error::SyncError,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct BulkProgressPayload {
+    operation: String,
+    result: bulk::ProjectResult,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct SearchResultsPayload {
+    search: String,
+    result: search::ProjectMatches,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectCleanupReport {
+    id: Uuid,
+    name: Option<String>,
+    report: cleanup::CleanupReport,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectDiskUsage {
+    id: Uuid,
+    name: Option<String>,
+    usage: disk::DiskUsage,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectArtifactCleanup {
+    id: Uuid,
+    name: Option<String>,
+    reclaimable: u64,
+    removals: Vec<disk::ArtifactRemoval>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectObjectStats {
+    id: Uuid,
+    name: Option<String>,
+    objects: maintenance::ObjectStats,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectSecrets {
+    id: Uuid,
+    name: Option<String>,
+    worktree: Vec<secrets::SecretFinding>,
+    unpushed: Vec<secrets::SecretFinding>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectTodos {
+    id: Uuid,
+    name: Option<String>,
+    todos: Vec<todos::TodoItem>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectAudit {
+    id: Uuid,
+    name: Option<String>,
+    report: audit::AuditReport,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectLicenses {
+    id: Uuid,
+    name: Option<String>,
+    report: licenses::LicenseReport,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProjectStashes {
+    id: Uuid,
+    name: Option<String>,
+    stashes: Vec<stash::StashInfo>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct TagComparison {
+    local_only: Vec<String>,
+    remote_only: Vec<String>,
+    mismatched: Vec<String>,
+    releases: Vec<repos::Release>,
+    releases_without_local_tag: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct TimelineEntry {
+    id: Uuid,
+    name: Option<String>,
+    commit: history::CommitInfo,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct CloneProgressPayload {
+    id: String,
+    progress: clone::TransferProgress,
+}
+
+impl From<branch::Error> for Error {
+    fn from(e: branch::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            branch::Error::WouldClobber(_) => Error::WouldClobberError,
+            branch::Error::NotMerged(_) => Error::NotMergedError,
+            branch::Error::CheckedOut(_) => Error::CheckedOutError,
+            _ => Error::BranchError,
+        }
+    }
+}
+
+impl From<stage::Error> for Error {
+    fn from(e: stage::Error) -> Self {
+        log::error!("{:?}", e);
+        Error::StageError
+    }
+}
+
+impl From<commit::Error> for Error {
+    fn from(e: commit::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            commit::Error::NothingToCommit => Error::NothingToCommitError,
+            commit::Error::EmptyMessage => Error::EmptyMessageError,
+            _ => Error::CommitError,
+        }
+    }
+}
+
+impl From<version::Error> for Error {
+    fn from(e: version::Error) -> Self {
+        log::error!("{:?}", e);
+        match e {
+            version::Error::NoManifest => Error::NoManifestError,
+            version::Error::NothingToRelease(_) => Error::NothingToReleaseError,
+            version::Error::DirtyWorktree => Error::DirtyWorktreeError,
+            _ => Error::VersionError,
+        }
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, Error> {
+    Uuid::try_parse(id).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::UuidParseError
+    })
+}
+
+fn get_project(projects_state: &ProjectsState, key: &Uuid) -> Result<Project, Error> {
+    let projects = projects_state.0.lock().unwrap();
+    Ok(projects.get(key).ok_or(Error::UuidNoMatch)?.clone())
+}
+
+fn all_projects(projects_state: &ProjectsState) -> Vec<(Uuid, Project)> {
+    let projects = projects_state.0.lock().unwrap();
+    projects.iter().map(|(k, p)| (*k, p.clone())).collect()
+}
+
+fn cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, Error> {
+    app_handle
+        .path_resolver()
+        .app_data_dir()
+        .map(|d| d.join("cache"))
+        .ok_or(Error::NoAppDataError)
+}
+
+fn open_repository(prj: &Project) -> Result<git2::Repository, Error> {
+    prj.open_repository().map_err(|e| match e {
+        project::Error::NoLocalProject => Error::NoLocalError,
+        project::Error::NoGit => Error::NoGitError,
+        e => {
+            log::error!("{:?}", e);
+            Error::GitError
+        }
+    })
+}
+
+fn refresh_project(
+    app_handle: &tauri::AppHandle,
+    projects_state: &ProjectsState,
+    key: Uuid,
+    prj: &Project,
+) -> Result<(), Error> {
+    let path = prj.local_path().map_err(|_| Error::NoLocalError)?;
+    let local =
+        project::read_local_project(path, &cache_dir(app_handle)?).ok_or(Error::GitError)?;
+    let projects = &mut *projects_state.0.lock().unwrap();
+    projects.insert(key, Project::new(Some(local), prj.remote.clone()));
+    Ok(())
+}
+
+fn check_changed_paths(prj: &Project, paths: &[String]) -> Result<(), Error> {
+    let changes = prj.changes().ok_or(Error::NoGitError)?;
+    let known = paths
+        .iter()
+        .all(|p| changes.iter().any(|c| c.path.as_ref() == Some(p)));
+    if !known {
+        return Err(Error::InvalidPathError);
+    }
+    Ok(())
+}
+
+fn get_token(token_state: &TokenState) -> Result<String, Error> {
+    let token_guard = token_state.0.lock().unwrap();
+    token_guard.clone().ok_or(Error::NoTokenError)
 }
 
 #[tauri::command]
 fn update_projects(
+    app_handle: tauri::AppHandle,
     token_state: tauri::State<TokenState>,
     projects_state: tauri::State<ProjectsState>,
     project_dirs: tauri::State<ProjectDirState>,
@@ -63,6 +412,7 @@ fn update_projects(
     let task = block_on(project::list_projects(
         &token,
         &dirs,
+        &cache_dir(&app_handle)?,
         &ListParameters {
             visibility: Some("all".into()),
             affiliation: None,
@@ -90,9 +440,34 @@ fn update_projects(
 }
 
 #[tauri::command]
-fn project_ids(projects_state: tauri::State<ProjectsState>) -> Result<Vec<Uuid>, Error> {
+fn project_ids(
+    projects_state: tauri::State<ProjectsState>,
+    kinds: Option<Vec<ProjectKind>>,
+) -> Result<Vec<Uuid>, Error> {
     let projects = &*projects_state.0.lock().unwrap();
-    Ok(projects.ids())
+    let kinds = match kinds {
+        Some(k) if !k.is_empty() => k,
+        _ => return Ok(projects.ids()),
+    };
+    Ok(projects
+        .iter()
+        .filter(|(_, p)| {
+            p.manifest()
+                .map(|m| m.kinds.iter().any(|k| kinds.contains(k)))
+                .unwrap_or(false)
+        })
+        .map(|(id, _)| *id)
+        .collect())
+}
+
+#[tauri::command]
+fn project_manifest(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<Option<ManifestSummary>, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    Ok(prj.manifest())
 }
 
 #[tauri::command]
@@ -218,32 +593,1545 @@ fn project_changes(
     }
 }
 
-fn main() {
-    env_logger::init();
-    dotenv::dotenv().ok();
+#[tauri::command]
+fn project_dirs(project_dirs: tauri::State<ProjectDirState>) -> Result<Vec<String>, Error> {
+    let dirs = project_dirs.0.lock().unwrap();
+    dirs.iter()
+        .map(|d| {
+            d.to_str()
+                .map(|s| s.to_string())
+                .ok_or(Error::PathParseError)
+        })
+        .collect()
+}
 
-    let token = match dotenv::var("token") {
-        Ok(t) => Some(t),
+#[tauri::command(async)]
+fn clone_project(
+    window: tauri::Window,
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    project_dirs: tauri::State<ProjectDirState>,
+    id: String,
+    root: String,
+    url_type: RemoteUrlType,
+) -> Result<(), Error> {
+    log::info!("clone_project");
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    if prj.local_name().is_some() {
+        return Err(Error::LocalExistsError);
+    }
+    let repository = prj.remote.clone().ok_or(Error::NoRemoteError)?;
+
+    let root = PathBuf::from(root);
+    if !project_dirs.0.lock().unwrap().contains(&root) {
+        return Err(Error::InvalidPathError);
+    }
+    let destination = root.join(&repository.name);
+    let url = remote::remote_url(&repository, &url_type).ok_or(Error::NoRemoteError)?;
+    let token = get_token(&token_state).ok();
+
+    let cloned = clone::clone_repository(&url, &destination, token, |progress| {
+        let payload = CloneProgressPayload {
+            id: id.clone(),
+            progress,
+        };
+        if let Err(e) = window.emit("clone-progress", payload) {
+            log::warn!("{:?}", e);
+        }
+    });
+    if let Err(e) = cloned {
+        log::error!("{:?}", e);
+        return Err(Error::CloneError);
+    }
+
+    let local = project::read_local_project(destination, &cache_dir(&window.app_handle())?)
+        .ok_or(Error::CloneError)?;
+    let projects = &mut *projects_state.0.lock().unwrap();
+    projects.insert(key, Project::new(Some(local), Some(repository)));
+    Ok(())
+}
+
+#[tauri::command]
+async fn publish_project(
+    app_handle: tauri::AppHandle,
+    token_state: tauri::State<'_, TokenState>,
+    projects_state: tauri::State<'_, ProjectsState>,
+    id: String,
+    params: repos::CreateParameters,
+    url_type: RemoteUrlType,
+) -> Result<String, Error> {
+    log::info!("publish_project");
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    if prj.remote.is_some() {
+        return Err(Error::RemoteExistsError);
+    }
+    let token = get_token(&token_state)?;
+    let branch = remote::current_branch(&open_repository(&prj)?).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::PublishError
+    })?;
+
+    let existing = match repos::find_repository(&token, &params).await {
+        Ok(r) => r,
         Err(e) => {
-            log::warn!("{}", e);
-            None
+            log::error!("{:?}", e);
+            return Err(Error::PublishError);
         }
     };
-    log::debug!("{:?}", token);
+    if existing.is_some() {
+        return Err(Error::RemoteExistsError);
+    }
+    let published = match repos::create_repository(&token, &params).await {
+        Ok(r) => r,
+        Err(e) => {
+            log::error!("{:?}", e);
+            return Err(Error::PublishError);
+        }
+    };
+
+    let repository = open_repository(&prj)?;
+    let (remote_name, added) =
+        remote::publish_remote(&repository, &published, &url_type).map_err(|e| {
+            log::error!("{:?}", e);
+            Error::PublishError
+        })?;
+    if let Err(e) =
+        remote::push_branch(&repository, &remote_name, &branch, Some(token), true, false)
+    {
+        log::error!("{:?}", e);
+        if added {
+            if let Err(e) = repository.remote_delete(&remote_name) {
+                log::warn!("{:?}", e);
+            }
+        }
+        return Err(Error::PublishError);
+    }
+
+    let path = prj.local_path().map_err(|_| Error::NoLocalError)?;
+    let local =
+        project::read_local_project(path, &cache_dir(&app_handle)?).ok_or(Error::PublishError)?;
+    let projects = &mut *projects_state.0.lock().unwrap();
+    projects.insert(key, Project::new(Some(local), Some(published)));
+    Ok(remote_name)
+}
+
+#[tauri::command(async)]
+fn pull(
+    app_handle: tauri::AppHandle,
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    branch: Option<String>,
+    options: sync::SyncOptions,
+) -> Result<sync::SyncResult, Error> {
+    log::info!("pull");
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+    let token = get_token(&token_state).ok();
+
+    let result = sync::pull(&repository, branch.as_deref(), &options, token)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(result)
+}
+
+#[tauri::command(async)]
+fn push(
+    app_handle: tauri::AppHandle,
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    branch: Option<String>,
+    options: sync::SyncOptions,
+) -> Result<sync::SyncResult, Error> {
+    log::info!("push");
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+    let token = get_token(&token_state).ok();
+
+    let result = sync::push(&repository, branch.as_deref(), &options, token)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(result)
+}
+
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn bulk_operation(
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    bulk_state: tauri::State<BulkState>,
+    operation: String,
+    action: bulk::BulkAction,
+    ids: Vec<String>,
+    concurrency: Option<usize>,
+    dry_run: bool,
+) -> Result<bulk::BulkSummary, Error> {
+    log::info!("bulk_operation");
+    let operation_key = parse_id(&operation)?;
+    let mut targets = Vec::new();
+    for id in ids.iter() {
+        let key = parse_id(id)?;
+        targets.push((key, get_project(&projects_state, &key)?));
+    }
+    let token = get_token(&token_state).ok();
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    bulk_state
+        .0
+        .lock()
+        .unwrap()
+        .insert(operation_key, cancel.clone());
+
+    let summary = bulk::run(
+        action,
+        targets.clone(),
+        token,
+        concurrency.unwrap_or(4),
+        dry_run,
+        cancel,
+        move |result| {
+            let payload = BulkProgressPayload {
+                operation: operation.clone(),
+                result: result.clone(),
+            };
+            if let Err(e) = window.emit("bulk-progress", payload) {
+                log::warn!("{:?}", e);
+            }
+        },
+    );
+    bulk_state.0.lock().unwrap().remove(&operation_key);
+
+    if !dry_run {
+        for (key, prj) in targets.iter() {
+            if let Err(e) = refresh_project(&app_handle, &projects_state, *key, prj) {
+                log::warn!("{:?}", e);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[tauri::command]
+fn cancel_bulk_operation(
+    bulk_state: tauri::State<BulkState>,
+    operation: String,
+) -> Result<(), Error> {
+    let operation_key = parse_id(&operation)?;
+    let operations = bulk_state.0.lock().unwrap();
+    let cancel = operations.get(&operation_key).ok_or(Error::UuidNoMatch)?;
+    cancel.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+fn create_branch(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    from: String,
+) -> Result<String, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let commit = branch::create_branch(&repository, &name, &from)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(commit)
+}
+
+#[tauri::command]
+fn checkout_branch(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    branch::checkout_branch(&repository, &name)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn rename_branch(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    new_name: String,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    branch::rename_branch(&repository, &name, &new_name)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn delete_branch(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    force: bool,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    branch::delete_branch(&repository, &name, force)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command(async)]
+fn delete_remote_branch(
+    app_handle: tauri::AppHandle,
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    remote_name: Option<String>,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+    let token = get_token(&token_state).ok();
+    let remote_name = remote_name.unwrap_or_else(|| String::from("origin"));
+
+    if let Err(e) = remote::delete_remote_branch(&repository, &remote_name, &name, token) {
+        log::error!("{:?}", e);
+        return Err(Error::BranchError);
+    }
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+const DEFAULT_INACTIVE_DAYS: i64 = 90;
+
+#[tauri::command]
+fn branch_cleanup_report(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    inactive_days: Option<i64>,
+) -> Result<cleanup::CleanupReport, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+    let commits = prj.local_commits().unwrap_or_default();
+
+    let days = inactive_days.unwrap_or(DEFAULT_INACTIVE_DAYS);
+    cleanup::cleanup_report(&repository, &commits, days).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::CleanupError
+    })
+}
+
+#[tauri::command(async)]
+fn all_branch_cleanup_reports(
+    projects_state: tauri::State<ProjectsState>,
+    inactive_days: Option<i64>,
+) -> Result<Vec<ProjectCleanupReport>, Error> {
+    let days = inactive_days.unwrap_or(DEFAULT_INACTIVE_DAYS);
+    let reports = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let repository = prj.open_repository().ok()?;
+            let commits = prj.local_commits().unwrap_or_default();
+            let report = match cleanup::cleanup_report(&repository, &commits, days) {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!("{:?}", e);
+                    return None;
+                }
+            };
+            if report.branches.is_empty() {
+                return None;
+            }
+            Some(ProjectCleanupReport {
+                id,
+                name: prj.local_name(),
+                report,
+            })
+        })
+        .collect();
+    Ok(reports)
+}
+
+#[tauri::command]
+fn delete_stale_branches(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    names: Vec<String>,
+) -> Result<Vec<cleanup::BranchDeletion>, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let deletions = cleanup::delete_branches(&repository, &names);
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(deletions)
+}
+
+#[tauri::command]
+fn projects_with_stashes(
+    projects_state: tauri::State<ProjectsState>,
+) -> Result<Vec<ProjectStashes>, Error> {
+    let stashes = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let stashes = prj.stashes()?;
+            if stashes.is_empty() {
+                return None;
+            }
+            Some(ProjectStashes {
+                id,
+                name: prj.local_name(),
+                stashes,
+            })
+        })
+        .collect();
+    Ok(stashes)
+}
+
+#[tauri::command(async)]
+fn stash_diff(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    index: usize,
+) -> Result<stash::StashDiff, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let mut repository = open_repository(&prj)?;
+
+    Ok(stash::stash_diff(&mut repository, index)?)
+}
+
+#[tauri::command]
+fn stash_apply(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    index: usize,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let mut repository = open_repository(&prj)?;
+
+    stash::apply(&mut repository, index)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn stash_pop(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    index: usize,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let mut repository = open_repository(&prj)?;
+
+    stash::pop(&mut repository, index)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn stash_drop(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    index: usize,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let mut repository = open_repository(&prj)?;
+
+    stash::drop(&mut repository, index)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command(async)]
+fn project_diff(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    target: diff::DiffTarget,
+    path: Option<String>,
+    max_lines: Option<usize>,
+) -> Result<diff::DiffResult, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let max_lines = max_lines.unwrap_or(diff::MAX_LINES_PER_FILE);
+    diff::diff(&repository, &target, path.as_deref())
+        .and_then(|d| diff::file_diffs(&d, max_lines))
+        .map_err(|e| {
+            log::error!("{:?}", e);
+            Error::DiffError
+        })
+}
+
+#[tauri::command]
+fn stage_files(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    paths: Vec<String>,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    check_changed_paths(&prj, &paths)?;
+    let repository = open_repository(&prj)?;
+
+    stage::stage_paths(&repository, &paths)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn unstage_files(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    paths: Vec<String>,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    check_changed_paths(&prj, &paths)?;
+    let repository = open_repository(&prj)?;
+
+    stage::unstage_paths(&repository, &paths)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn stage_hunk(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    path: String,
+    old_start: u32,
+    new_start: u32,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    check_changed_paths(&prj, std::slice::from_ref(&path))?;
+    let repository = open_repository(&prj)?;
+
+    stage::stage_hunk(&repository, &path, old_start, new_start)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn unstage_hunk(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    path: String,
+    old_start: u32,
+    new_start: u32,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    check_changed_paths(&prj, std::slice::from_ref(&path))?;
+    let repository = open_repository(&prj)?;
+
+    stage::unstage_hunk(&repository, &path, old_start, new_start)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn discard_changes(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    paths: Vec<String>,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    check_changed_paths(&prj, &paths)?;
+    let repository = open_repository(&prj)?;
+
+    stage::discard_paths(&repository, &paths)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)
+}
+
+#[tauri::command]
+fn commit_changes(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    message: String,
+) -> Result<String, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let oid = commit::commit(&repository, &message)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(oid.to_string())
+}
+
+const DEFAULT_LARGE_BLOBS: usize = 20;
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[tauri::command]
+fn commit_log(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    filter: history::LogFilter,
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> Result<Vec<history::CommitInfo>, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let per_page = per_page.unwrap_or(DEFAULT_PAGE_SIZE);
+    let skip = page.unwrap_or(0) * per_page;
+    history::commit_log(&repository, &filter, skip, per_page).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::LogError
+    })
+}
+
+#[tauri::command(async)]
+fn activity_timeline(
+    projects_state: tauri::State<ProjectsState>,
+    limit: Option<usize>,
+) -> Result<Vec<TimelineEntry>, Error> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let mut timeline = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let repository = prj.open_repository().ok()?;
+            let commits = match history::latest_commits(&repository, limit) {
+                Ok(c) => c,
+                Err(e) => {
+                    log::warn!("{:?}", e);
+                    return None;
+                }
+            };
+            let name = prj.local_name();
+            Some(commits.into_iter().map(move |commit| TimelineEntry {
+                id,
+                name: name.clone(),
+                commit,
+            }))
+        })
+        .flatten()
+        .collect::<Vec<TimelineEntry>>();
+    timeline.sort_by_key(|t| Reverse(t.commit.date));
+    timeline.truncate(limit);
+    Ok(timeline)
+}
+
+#[tauri::command]
+fn commit_graph(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    branches: Vec<String>,
+    limit: Option<usize>,
+) -> Result<graph::GraphLayout, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let limit = limit.unwrap_or(graph::DEFAULT_LIMIT);
+    graph::commit_graph(&repository, &branches, limit).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::GraphError
+    })
+}
+
+fn compare_tags(
+    local: &[tag::TagInfo],
+    remote: &[repos::RemoteTag],
+    releases: Vec<repos::Release>,
+) -> TagComparison {
+    let remote_map = remote
+        .iter()
+        .map(|t| (t.name.as_str(), t.sha.as_str()))
+        .collect::<HashMap<&str, &str>>();
+    let local_map = local
+        .iter()
+        .map(|t| (t.name.as_str(), t.target.as_str()))
+        .collect::<HashMap<&str, &str>>();
+
+    let mut local_only = Vec::new();
+    let mut mismatched = Vec::new();
+    for (name, target) in local_map.iter() {
+        match remote_map.get(name) {
+            None => local_only.push(name.to_string()),
+            Some(sha) if sha != target => mismatched.push(name.to_string()),
+            Some(_) => {}
+        }
+    }
+    let mut remote_only = remote_map
+        .keys()
+        .filter(|name| !local_map.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect::<Vec<String>>();
+    let releases_without_local_tag = releases
+        .iter()
+        .filter(|r| !local_map.contains_key(r.tag_name.as_str()))
+        .map(|r| r.tag_name.clone())
+        .collect();
+    local_only.sort();
+    remote_only.sort();
+    mismatched.sort();
+
+    TagComparison {
+        local_only,
+        remote_only,
+        mismatched,
+        releases,
+        releases_without_local_tag,
+    }
+}
+
+#[tauri::command]
+async fn tag_comparison(
+    token_state: tauri::State<'_, TokenState>,
+    projects_state: tauri::State<'_, ProjectsState>,
+    id: String,
+) -> Result<TagComparison, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let local = prj.tags().ok_or(Error::NoGitError)?;
+    let repository = prj.remote.clone().ok_or(Error::NoRemoteError)?;
+    let token = get_token(&token_state)?;
+
+    let remote = repos::list_tags(&token, &repository).await.map_err(|e| {
+        log::error!("{:?}", e);
+        Error::RemoteTagsError
+    })?;
+    let releases = repos::list_releases(&token, &repository)
+        .await
+        .map_err(|e| {
+            log::error!("{:?}", e);
+            Error::RemoteTagsError
+        })?;
+    Ok(compare_tags(&local, &remote, releases))
+}
+
+#[tauri::command]
+fn commits_since_last_tag(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<tag::CommitsSinceTag, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    Ok(tag::commits_since_last_tag(&repository)?)
+}
+
+#[tauri::command]
+fn create_tag(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    target: Option<String>,
+    message: String,
+) -> Result<String, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let target = target.unwrap_or_else(|| String::from("HEAD"));
+    let oid = tag::create_tag(&repository, &name, &target, &message)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(oid.to_string())
+}
+
+#[tauri::command(async)]
+fn push_tag(
+    token_state: tauri::State<TokenState>,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    name: String,
+    remote_name: Option<String>,
+) -> Result<(), Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+    let token = get_token(&token_state).ok();
+    let remote_name = remote_name.unwrap_or_else(|| String::from("origin"));
+
+    let refspec = format!("refs/tags/{0}:refs/tags/{0}", name);
+    if let Err(e) = remote::push_refspec(&repository, &remote_name, &refspec, token) {
+        log::error!("{:?}", e);
+        return Err(Error::TagError);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn generate_changelog(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    from: Option<String>,
+    to: Option<String>,
+    format: changelog::ChangelogFormat,
+) -> Result<String, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    changelog::changelog(
+        &repository,
+        from.as_deref(),
+        to.as_deref(),
+        prj.remote.as_ref(),
+    )
+    .and_then(|c| changelog::render(&c, &format))
+    .map_err(|e| {
+        log::error!("{:?}", e);
+        Error::ChangelogError
+    })
+}
+
+#[tauri::command]
+fn bump_version(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    level: Option<version::BumpLevel>,
+    dry_run: bool,
+) -> Result<version::VersionPlan, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let plan = version::plan(&repository, level)?;
+    if dry_run {
+        return Ok(plan);
+    }
+    version::apply(&repository, &plan)?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(plan)
+}
+
+#[tauri::command]
+fn project_languages(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<Vec<languages::LanguageStats>, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    prj.languages().ok_or(Error::NoLocalError)
+}
+
+#[tauri::command]
+fn language_summary(
+    projects_state: tauri::State<ProjectsState>,
+) -> Result<Vec<languages::LanguageTotal>, Error> {
+    let projects = all_projects(&projects_state);
+    Ok(languages::summarize(
+        projects.iter().filter_map(|(_, p)| p.languages()),
+    ))
+}
+
+#[tauri::command(async)]
+fn disk_usage(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<disk::DiskUsage, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let path = prj.local_path().map_err(|_| Error::NoLocalError)?;
+
+    disk::disk_usage(&path).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::DiskUsageError
+    })
+}
+
+#[tauri::command(async)]
+fn all_disk_usage(
+    projects_state: tauri::State<ProjectsState>,
+) -> Result<Vec<ProjectDiskUsage>, Error> {
+    let mut usages = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let path = prj.local_path().ok()?;
+            let usage = match disk::disk_usage(&path) {
+                Ok(u) => u,
+                Err(e) => {
+                    log::warn!("{:?}", e);
+                    return None;
+                }
+            };
+            Some(ProjectDiskUsage {
+                id,
+                name: prj.local_name(),
+                usage,
+            })
+        })
+        .collect::<Vec<ProjectDiskUsage>>();
+    usages.sort_by_key(|u| Reverse(u.usage.total));
+    Ok(usages)
+}
+
+#[tauri::command(async)]
+fn clean_artifacts(
+    projects_state: tauri::State<ProjectsState>,
+    ids: Option<Vec<String>>,
+    kinds: Vec<disk::ArtifactKind>,
+    inactive_days: Option<i64>,
+    dry_run: bool,
+) -> Result<Vec<ProjectArtifactCleanup>, Error> {
+    let targets = match ids {
+        Some(ids) => {
+            let keys = ids
+                .iter()
+                .map(|id| parse_id(id))
+                .collect::<Result<Vec<Uuid>, Error>>()?;
+            all_projects(&projects_state)
+                .into_iter()
+                .filter(|(id, _)| keys.contains(id))
+                .collect()
+        }
+        None if dry_run => all_projects(&projects_state),
+        None => return Err(Error::NoProjectsSelectedError),
+    };
+    let days = inactive_days.unwrap_or(DEFAULT_INACTIVE_DAYS);
+
+    let cleanups = targets
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let path = prj.local_path().ok()?;
+            let usage = match disk::disk_usage(&path) {
+                Ok(u) => u,
+                Err(e) => {
+                    log::warn!("{:?}", e);
+                    return None;
+                }
+            };
+            if !disk::is_inactive(&usage, days) {
+                return None;
+            }
+            let removals = disk::remove_artifacts(&path, &usage, &kinds, dry_run);
+            if removals.is_empty() {
+                return None;
+            }
+            Some(ProjectArtifactCleanup {
+                id,
+                name: prj.local_name(),
+                reclaimable: removals.iter().map(|r| r.bytes).sum(),
+                removals,
+            })
+        })
+        .collect();
+    Ok(cleanups)
+}
+
+#[tauri::command(async)]
+fn maintenance_report(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    limit: Option<usize>,
+) -> Result<maintenance::MaintenanceReport, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LARGE_BLOBS);
+    maintenance::report(&repository, limit).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::MaintenanceError
+    })
+}
+
+#[tauri::command(async)]
+fn all_object_stats(
+    projects_state: tauri::State<ProjectsState>,
+) -> Result<Vec<ProjectObjectStats>, Error> {
+    let mut stats = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| {
+            let repository = prj.open_repository().ok()?;
+            let objects = maintenance::object_stats(repository.path())
+                .map_err(|e| log::warn!("[all_object_stats] {:?}", e))
+                .ok()?;
+            Some(ProjectObjectStats {
+                id,
+                name: prj.local_name(),
+                objects,
+            })
+        })
+        .collect::<Vec<ProjectObjectStats>>();
+    stats.sort_by(|a, b| {
+        (b.objects.pack_size + b.objects.loose_size)
+            .cmp(&(a.objects.pack_size + a.objects.loose_size))
+    });
+    Ok(stats)
+}
+
+#[tauri::command(async)]
+fn run_gc(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    aggressive: bool,
+) -> Result<maintenance::RepackResult, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let cancel = AtomicBool::new(false);
+    let result = if aggressive {
+        maintenance::repack(&repository, &cancel)
+    } else {
+        maintenance::gc(&repository, &cancel)
+    };
+    let result = result.map_err(|e| {
+        log::error!("{:?}", e);
+        Error::MaintenanceError
+    })?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(result)
+}
+
+#[tauri::command(async)]
+fn run_repack(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<maintenance::RepackResult, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let repository = open_repository(&prj)?;
+
+    let result = maintenance::repack(&repository, &AtomicBool::new(false)).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::MaintenanceError
+    })?;
+    refresh_project(&app_handle, &projects_state, key, &prj)?;
+    Ok(result)
+}
+
+fn secret_scanner(rules_state: &SecretRulesState) -> Result<secrets::Scanner, Error> {
+    let rules = rules_state.0.lock().unwrap().clone();
+    secrets::Scanner::new(&rules).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::InvalidRuleError
+    })
+}
+
+fn scan_project_secrets(
+    scanner: &secrets::Scanner,
+    id: Uuid,
+    prj: &Project,
+) -> Result<ProjectSecrets, Error> {
+    let repository = open_repository(prj)?;
+    let worktree = scanner.scan_worktree(&repository).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::SecretScanError
+    })?;
+    let unpushed = scanner.scan_unpushed(&repository).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::SecretScanError
+    })?;
+    Ok(ProjectSecrets {
+        id,
+        name: prj.local_name(),
+        worktree,
+        unpushed,
+    })
+}
+
+#[tauri::command]
+fn secret_rules(
+    rules_state: tauri::State<SecretRulesState>,
+) -> Result<Vec<secrets::SecretRule>, Error> {
+    Ok(rules_state.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+fn set_secret_rules(
+    rules_state: tauri::State<SecretRulesState>,
+    rules: Vec<secrets::SecretRule>,
+) -> Result<(), Error> {
+    secrets::Scanner::new(&rules).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::InvalidRuleError
+    })?;
+    *rules_state.0.lock().unwrap() = rules;
+    Ok(())
+}
+
+#[tauri::command(async)]
+fn scan_secrets(
+    projects_state: tauri::State<ProjectsState>,
+    rules_state: tauri::State<SecretRulesState>,
+    id: String,
+) -> Result<ProjectSecrets, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let scanner = secret_scanner(&rules_state)?;
+    scan_project_secrets(&scanner, key, &prj)
+}
+
+#[tauri::command(async)]
+fn scan_all_secrets(
+    projects_state: tauri::State<ProjectsState>,
+    rules_state: tauri::State<SecretRulesState>,
+) -> Result<Vec<ProjectSecrets>, Error> {
+    let scanner = secret_scanner(&rules_state)?;
+    let reports = all_projects(&projects_state)
+        .into_iter()
+        .filter(|(_, prj)| prj.changes().is_some())
+        .filter_map(|(id, prj)| match scan_project_secrets(&scanner, id, &prj) {
+            Ok(r) if r.worktree.is_empty() && r.unpushed.is_empty() => None,
+            Ok(r) => Some(r),
+            Err(e) => {
+                log::warn!("{:?}", e);
+                None
+            }
+        })
+        .collect();
+    Ok(reports)
+}
+
+#[tauri::command(async)]
+fn search_projects(
+    window: tauri::Window,
+    projects_state: tauri::State<ProjectsState>,
+    bulk_state: tauri::State<BulkState>,
+    search: String,
+    query: search::SearchQuery,
+    ids: Option<Vec<String>>,
+    kinds: Option<Vec<ProjectKind>>,
+) -> Result<search::SearchSummary, Error> {
+    let search_key = parse_id(&search)?;
+    let ids = match ids {
+        Some(ids) => Some(
+            ids.iter()
+                .map(|id| parse_id(id))
+                .collect::<Result<Vec<Uuid>, Error>>()?,
+        ),
+        None => None,
+    };
+    let mut targets = all_projects(&projects_state)
+        .into_iter()
+        .filter(|(id, _)| ids.as_ref().map(|ids| ids.contains(id)).unwrap_or(true))
+        .filter(|(_, prj)| match &kinds {
+            Some(kinds) if !kinds.is_empty() => prj
+                .manifest()
+                .map(|m| m.kinds.iter().any(|k| kinds.contains(k)))
+                .unwrap_or(false),
+            _ => true,
+        })
+        .collect::<Vec<(Uuid, Project)>>();
+    targets.sort_by_key(|(_, prj)| prj.local_name());
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    bulk_state
+        .0
+        .lock()
+        .unwrap()
+        .insert(search_key, cancel.clone());
+
+    let summary = search::run(&query, targets, &cancel, |result| {
+        let payload = SearchResultsPayload {
+            search: search.clone(),
+            result: result.clone(),
+        };
+        if let Err(e) = window.emit("search-results", payload) {
+            log::warn!("{:?}", e);
+        }
+    });
+    bulk_state.0.lock().unwrap().remove(&search_key);
+
+    summary.map_err(|e| {
+        log::error!("{:?}", e);
+        Error::SearchError
+    })
+}
+
+#[tauri::command(async)]
+fn list_todos(
+    app_handle: tauri::AppHandle,
+    projects_state: tauri::State<ProjectsState>,
+    ids: Option<Vec<String>>,
+    filter: Option<todos::TodoFilter>,
+) -> Result<Vec<ProjectTodos>, Error> {
+    let ids = match ids {
+        Some(ids) => Some(
+            ids.iter()
+                .map(|id| parse_id(id))
+                .collect::<Result<Vec<Uuid>, Error>>()?,
+        ),
+        None => None,
+    };
+    let filter = filter.unwrap_or_default();
+    let cache_dir = cache_dir(&app_handle)?;
+    let mut projects = all_projects(&projects_state)
+        .into_iter()
+        .filter(|(id, _)| ids.as_ref().map(|ids| ids.contains(id)).unwrap_or(true))
+        .filter_map(|(id, prj)| {
+            let repository = prj.open_repository().ok()?;
+            let todos = todos::list_todos(&cache_dir, &repository)
+                .map_err(|e| log::warn!("[list_todos] {:?}", e))
+                .ok()?;
+            let todos = todos::filter_todos(todos, &filter);
+            if todos.is_empty() {
+                return None;
+            }
+            Some(ProjectTodos {
+                id,
+                name: prj.local_name(),
+                todos,
+            })
+        })
+        .collect::<Vec<ProjectTodos>>();
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(projects)
+}
+
+#[tauri::command(async)]
+fn dependency_graph(
+    projects_state: tauri::State<ProjectsState>,
+) -> Result<dependencies::DependencyGraph, Error> {
+    Ok(dependencies::build(&all_projects(&projects_state)))
+}
+
+#[tauri::command(async)]
+fn project_dependents(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+) -> Result<Vec<Uuid>, Error> {
+    let key = parse_id(&id)?;
+    get_project(&projects_state, &key)?;
+    let graph = dependencies::build(&all_projects(&projects_state));
+    Ok(dependencies::dependents(&graph, &key))
+}
+
+fn write_env(path: &Path, values: &[(&str, Option<String>)]) -> std::io::Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut lines = content
+        .lines()
+        .filter(|line| {
+            let key = line.split('=').next().unwrap_or_default().trim();
+            !values.iter().any(|(k, _)| *k == key)
+        })
+        .map(String::from)
+        .collect::<Vec<String>>();
+    for (key, value) in values.iter() {
+        if let Some(value) = value {
+            lines.push(format!("{}={}", key, env_value(value)?));
+        }
+    }
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+fn env_value(value: &str) -> std::io::Result<String> {
+    if value.contains(['\n', '\r']) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Setting values cannot span lines",
+        ));
+    }
+    Ok(format!("'{}'", value.replace('\'', "'\\''")))
+}
+
+fn advisory_database(advisory_state: &AdvisoryState) -> Result<audit::AdvisoryDatabase, Error> {
+    let sources = advisory_state.0.lock().unwrap().clone();
+    audit::AdvisoryDatabase::load(&sources).map_err(|e| {
+        log::error!("{:?}", e);
+        Error::AdvisoryDatabaseError
+    })
+}
+
+#[tauri::command]
+fn advisory_sources(
+    advisory_state: tauri::State<AdvisoryState>,
+) -> Result<audit::AdvisorySources, Error> {
+    Ok(advisory_state.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+fn set_advisory_sources(
+    env_state: tauri::State<EnvState>,
+    advisory_state: tauri::State<AdvisoryState>,
+    sources: audit::AdvisorySources,
+) -> Result<(), Error> {
+    let path = |p: &Option<PathBuf>| p.as_ref().map(|p| p.to_string_lossy().to_string());
+    write_env(
+        &env_state.0,
+        &[
+            ("rustsec_db", path(&sources.rustsec)),
+            ("npm_advisories", path(&sources.npm)),
+        ],
+    )
+    .map_err(|e| {
+        log::error!("{:?}", e);
+        Error::SettingsError
+    })?;
+    *advisory_state.0.lock().unwrap() = sources;
+    Ok(())
+}
+
+#[tauri::command(async)]
+fn audit_project(
+    projects_state: tauri::State<ProjectsState>,
+    advisory_state: tauri::State<AdvisoryState>,
+    id: String,
+) -> Result<ProjectAudit, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let path = prj.local_path().map_err(|_| Error::NoLocalError)?;
+    let database = advisory_database(&advisory_state)?;
+    Ok(ProjectAudit {
+        id: key,
+        name: prj.local_name(),
+        report: database.audit(&path),
+    })
+}
+
+#[tauri::command(async)]
+fn audit_all_projects(
+    projects_state: tauri::State<ProjectsState>,
+    advisory_state: tauri::State<AdvisoryState>,
+) -> Result<Vec<ProjectAudit>, Error> {
+    let database = advisory_database(&advisory_state)?;
+    let mut audits = Vec::new();
+    for (id, prj) in all_projects(&projects_state) {
+        let path = match prj.local_path() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        let report = database.audit(&path);
+        if report.lockfiles.is_empty() {
+            continue;
+        }
+        audits.push(ProjectAudit {
+            id,
+            name: prj.local_name(),
+            report,
+        });
+    }
+    audits.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(audits)
+}
+
+#[tauri::command]
+fn license_policy(
+    policy_state: tauri::State<LicensePolicyState>,
+) -> Result<licenses::LicensePolicy, Error> {
+    Ok(policy_state.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+fn set_license_policy(
+    env_state: tauri::State<EnvState>,
+    policy_state: tauri::State<LicensePolicyState>,
+    policy: licenses::LicensePolicy,
+) -> Result<(), Error> {
+    let list = |l: &Vec<String>| Some(l.join(",")).filter(|l| !l.is_empty());
+    write_env(
+        &env_state.0,
+        &[
+            ("license_allow", list(&policy.allow)),
+            ("license_deny", list(&policy.deny)),
+        ],
+    )
+    .map_err(|e| {
+        log::error!("{:?}", e);
+        Error::SettingsError
+    })?;
+    *policy_state.0.lock().unwrap() = policy;
+    Ok(())
+}
+
+fn project_licenses(
+    id: Uuid,
+    prj: &Project,
+    policy: &licenses::LicensePolicy,
+) -> Option<ProjectLicenses> {
+    let path = prj.local_path().ok()?;
+    let github = prj.remote.as_ref().and_then(|r| r.license.clone());
+    Some(ProjectLicenses {
+        id,
+        name: prj.local_name(),
+        report: licenses::report(&path, github.as_deref(), policy),
+    })
+}
+
+#[tauri::command(async)]
+fn license_report(
+    projects_state: tauri::State<ProjectsState>,
+    policy_state: tauri::State<LicensePolicyState>,
+    id: String,
+) -> Result<ProjectLicenses, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let policy = policy_state.0.lock().unwrap().clone();
+    project_licenses(key, &prj, &policy).ok_or(Error::NoLocalError)
+}
+
+#[tauri::command(async)]
+fn all_license_reports(
+    projects_state: tauri::State<ProjectsState>,
+    policy_state: tauri::State<LicensePolicyState>,
+) -> Result<Vec<ProjectLicenses>, Error> {
+    let policy = policy_state.0.lock().unwrap().clone();
+    let mut reports = all_projects(&projects_state)
+        .into_iter()
+        .filter_map(|(id, prj)| project_licenses(id, &prj, &policy))
+        .collect::<Vec<ProjectLicenses>>();
+    reports.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(reports)
+}
+
+#[tauri::command(async)]
+fn export_sbom(
+    projects_state: tauri::State<ProjectsState>,
+    id: String,
+    format: sbom::SbomFormat,
+) -> Result<String, Error> {
+    let key = parse_id(&id)?;
+    let prj = get_project(&projects_state, &key)?;
+    let path = prj.local_path().map_err(|_| Error::NoLocalError)?;
+    sbom::subject(&prj)
+        .and_then(|subject| sbom::generate(&path, &subject, &format))
+        .map_err(|e| match e {
+            sbom::Error::NoLocalProject => Error::NoLocalError,
+            e => {
+                log::error!("{:?}", e);
+                Error::SbomError
+            }
+        })
+}
+
+fn main() {
+    env_logger::init();
+    let env_path = dotenv::dotenv().unwrap_or_else(|_| PathBuf::from(".env"));
+
+    let token = match dotenv::var("token") {
+        Ok(t) => Some(t),
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    };
+    log::debug!("{:?}", token);
+    let sources = audit::AdvisorySources {
+        rustsec: dotenv::var("rustsec_db").ok().map(PathBuf::from),
+        npm: dotenv::var("npm_advisories").ok().map(PathBuf::from),
+    };
+    let list = |key: &str| {
+        dotenv::var(key)
+            .map(|v| {
+                v.split(',')
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let policy = licenses::LicensePolicy {
+        allow: list("license_allow"),
+        deny: list("license_deny"),
+    };
     tauri::Builder::default()
         .manage(TokenState(Arc::new(Mutex::new(token))))
         .manage(ProjectDirState(Arc::new(Mutex::new(vec![PathBuf::from(
             r"/Users/sebastian/Documents/prj/",
         )]))))
         .manage(ProjectsState(Arc::new(Mutex::new(Projects::default()))))
+        .manage(BulkState(Arc::new(Mutex::new(HashMap::new()))))
+        .manage(SecretRulesState(Arc::new(Mutex::new(Vec::new()))))
+        .manage(EnvState(env_path))
+        .manage(AdvisoryState(Arc::new(Mutex::new(sources))))
+        .manage(LicensePolicyState(Arc::new(Mutex::new(policy))))
         .invoke_handler(tauri::generate_handler![
             update_projects,
             project_ids,
             project_remote_name,
             project_local_name,
             project_local_commits,
+            project_manifest,
+            project_languages,
+            language_summary,
+            disk_usage,
+            all_disk_usage,
+            clean_artifacts,
+            maintenance_report,
+            all_object_stats,
+            run_gc,
+            run_repack,
+            secret_rules,
+            set_secret_rules,
+            scan_secrets,
+            scan_all_secrets,
+            search_projects,
+            list_todos,
+            dependency_graph,
+            project_dependents,
+            advisory_sources,
+            set_advisory_sources,
+            audit_project,
+            audit_all_projects,
+            license_policy,
+            set_license_policy,
+            license_report,
+            all_license_reports,
+            export_sbom,
             branch_relation,
-            project_changes
+            project_changes,
+            project_dirs,
+            clone_project,
+            publish_project,
+            pull,
+            push,
+            bulk_operation,
+            cancel_bulk_operation,
+            create_branch,
+            checkout_branch,
+            rename_branch,
+            delete_branch,
+            delete_remote_branch,
+            branch_cleanup_report,
+            all_branch_cleanup_reports,
+            delete_stale_branches,
+            projects_with_stashes,
+            stash_diff,
+            stash_apply,
+            stash_pop,
+            stash_drop,
+            project_diff,
+            stage_files,
+            unstage_files,
+            stage_hunk,
+            unstage_hunk,
+            discard_changes,
+            commit_changes,
+            commit_log,
+            activity_timeline,
+            commit_graph,
+            tag_comparison,
+            commits_since_last_tag,
+            create_tag,
+            push_tag,
+            generate_changelog,
+            bump_version
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
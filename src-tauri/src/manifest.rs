@@ -0,0 +1,230 @@
+use std::{fs, path::Path};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ProjectKind {
+    Cargo,
+    CargoWorkspace,
+    Npm,
+    Yarn,
+    Pnpm,
+    Python,
+    Go,
+    Tauri,
+    Docker,
+    DockerCompose,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Toolchain {
+    pub tool: String,
+    pub version: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ManifestSummary {
+    pub kinds: Vec<ProjectKind>,
+    pub manifests: Vec<String>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub toolchains: Vec<Toolchain>,
+}
+
+impl ManifestSummary {
+    fn add(&mut self, kind: ProjectKind, manifest: &str) {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+        if !self.manifests.iter().any(|m| m == manifest) {
+            self.manifests.push(manifest.to_string());
+        }
+    }
+
+    fn identify(&mut self, name: Option<&str>, version: Option<&str>) {
+        if self.name.is_none() {
+            self.name = name.map(String::from);
+        }
+        if self.version.is_none() {
+            self.version = version.map(String::from);
+        }
+    }
+
+    fn toolchain(&mut self, tool: &str, version: &str, source: &str) {
+        let version = version.trim().trim_start_matches('v');
+        if version.is_empty() || self.toolchains.iter().any(|t| t.tool == tool) {
+            return;
+        }
+        self.toolchains.push(Toolchain {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            source: source.to_string(),
+        });
+    }
+}
+
+const PYTHON_MANIFESTS: [&str; 5] = [
+    "pyproject.toml",
+    "requirements.txt",
+    "setup.py",
+    "setup.cfg",
+    "Pipfile",
+];
+const COMPOSE_FILES: [&str; 4] = [
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "compose.yml",
+    "compose.yaml",
+];
+
+pub fn detect(path: &Path) -> ManifestSummary {
+    let mut summary = ManifestSummary::default();
+    detect_toolchains(path, &mut summary);
+
+    for manifest in ["Cargo.toml", "src-tauri/Cargo.toml"] {
+        if let Some(document) = read_toml(&path.join(manifest)) {
+            if document.get("workspace").is_some() {
+                summary.add(ProjectKind::CargoWorkspace, manifest);
+            }
+            if let Some(package) = document.get("package") {
+                summary.add(ProjectKind::Cargo, manifest);
+                summary.identify(
+                    package.get("name").and_then(|n| n.as_str()),
+                    package.get("version").and_then(|v| v.as_str()),
+                );
+                if let Some(rust) = package.get("rust-version").and_then(|v| v.as_str()) {
+                    summary.toolchain("rust", rust, manifest);
+                }
+            }
+        }
+    }
+
+    if let Some(json) = read_json(&path.join("package.json")) {
+        let kind = if path.join("pnpm-lock.yaml").is_file()
+            || path.join("pnpm-workspace.yaml").is_file()
+        {
+            ProjectKind::Pnpm
+        } else if path.join("yarn.lock").is_file() {
+            ProjectKind::Yarn
+        } else {
+            ProjectKind::Npm
+        };
+        summary.add(kind, "package.json");
+        summary.identify(json["name"].as_str(), json["version"].as_str());
+        if let Some(node) = json["engines"]["node"].as_str() {
+            summary.toolchain("node", node, "package.json");
+        }
+    }
+
+    for manifest in PYTHON_MANIFESTS {
+        if !path.join(manifest).is_file() {
+            continue;
+        }
+        summary.add(ProjectKind::Python, manifest);
+        if let Some(document) = read_toml(&path.join(manifest)) {
+            if let Some(project) = document
+                .get("project")
+                .or_else(|| document.get("tool").and_then(|t| t.get("poetry")))
+            {
+                summary.identify(
+                    project.get("name").and_then(|n| n.as_str()),
+                    project.get("version").and_then(|v| v.as_str()),
+                );
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(path.join("go.mod")) {
+        summary.add(ProjectKind::Go, "go.mod");
+        for line in content.lines() {
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("module"), Some(module)) => {
+                    summary.identify(module.rsplit('/').next(), None);
+                }
+                (Some("go"), Some(version)) => summary.toolchain("go", version, "go.mod"),
+                _ => (),
+            }
+        }
+    }
+
+    for config in ["src-tauri/tauri.conf.json", "tauri.conf.json"] {
+        if path.join(config).is_file() {
+            summary.add(ProjectKind::Tauri, config);
+        }
+    }
+    if path.join("Dockerfile").is_file() {
+        summary.add(ProjectKind::Docker, "Dockerfile");
+    }
+    for compose in COMPOSE_FILES {
+        if path.join(compose).is_file() {
+            summary.add(ProjectKind::DockerCompose, compose);
+        }
+    }
+    summary
+}
+
+fn detect_toolchains(path: &Path, summary: &mut ManifestSummary) {
+    if let Some(document) = read_toml(&path.join("rust-toolchain.toml")) {
+        if let Some(channel) = document
+            .get("toolchain")
+            .and_then(|t| t.get("channel"))
+            .and_then(|c| c.as_str())
+        {
+            summary.toolchain("rust", channel, "rust-toolchain.toml");
+        }
+    } else if let Some(channel) = read_first_line(&path.join("rust-toolchain")) {
+        summary.toolchain("rust", &channel, "rust-toolchain");
+    }
+
+    for (file, tool) in [
+        (".nvmrc", "node"),
+        (".node-version", "node"),
+        (".python-version", "python"),
+    ] {
+        if let Some(version) = read_first_line(&path.join(file)) {
+            summary.toolchain(tool, &version, file);
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(path.join(".tool-versions")) {
+        for line in content.lines() {
+            let mut words = line.split_whitespace();
+            let tool = match words.next() {
+                Some("nodejs") => "node",
+                Some("golang") => "go",
+                Some(t) if !t.starts_with('#') => t,
+                _ => continue,
+            };
+            if let Some(version) = words.next() {
+                summary.toolchain(tool, version, ".tool-versions");
+            }
+        }
+    }
+}
+
+pub fn read_toml(path: &Path) -> Option<toml_edit::Document> {
+    if path.extension().map(|e| e != "toml").unwrap_or(true) {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .parse::<toml_edit::Document>()
+        .map_err(|e| log::warn!("{:?}: {:?}", path, e))
+        .ok()
+}
+
+pub fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| log::warn!("{:?}: {:?}", path, e))
+        .ok()
+}
+
+fn read_first_line(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+}
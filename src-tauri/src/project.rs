@@ -1,12 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use uuid::Uuid;
 
+use crate::git::{
+    languages::{self, LanguageStats},
+    stash::{self, StashInfo},
+    tag::{self, TagInfo},
+};
 use crate::github::user::{self, list_repos, ListParameters, Repository};
+use crate::manifest::{self, ManifestSummary};
 
 // ********** Projects **********
 
@@ -43,9 +49,17 @@ impl Projects {
     pub fn ids(&self) -> Vec<Uuid> {
         self.projects.keys().cloned().collect()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &Project)> {
+        self.projects.iter()
+    }
+
+    pub fn insert(&mut self, id: Uuid, project: Project) -> Option<Project> {
+        self.projects.insert(id, project)
+    }
 }
 
-pub fn list_local_projects(path: &PathBuf) -> Result<Vec<LocalProject>, Error> {
+pub fn list_local_projects(path: &PathBuf, cache_dir: &Path) -> Result<Vec<LocalProject>, Error> {
     let v = fs::read_dir(path)?
         .filter_map(|dir| {
             let dir = dir
@@ -58,108 +72,147 @@ pub fn list_local_projects(path: &PathBuf) -> Result<Vec<LocalProject>, Error> {
             if !path.is_dir() {
                 return None;
             }
-            if !path.join(".git").is_dir() {
-                return Some(LocalProject { path, git: None });
-            }
+            read_local_project(path, cache_dir)
+        })
+        .collect::<Vec<LocalProject>>();
 
-            let repository = git2::Repository::open(path.clone())
-                .or_else(|e| {
-                    log::error!("{:?}", e);
-                    Err(e)
-                })
-                .ok()?;
+    Ok(v)
+}
 
-            let remote_names = match repository.remotes() {
-                Ok(rs) => rs
-                    .iter()
-                    .filter_map(|r| r.map(|r| r.to_string()))
-                    .collect::<Vec<String>>(),
-                Err(e) => {
-                    log::error!("{:?}", e);
-                    Vec::new()
-                }
-            };
-            let remotes = remote_names
-                .iter()
-                .filter_map(|name| {
-                    let remote = repository.find_remote(name).ok()?;
-                    let url = remote.url()?.to_string();
-                    let url_type = match url.get(0..4) {
-                        Some("http") => RemoteUrlType::HTTP,
-                        Some("git@") => RemoteUrlType::SSH,
-                        _ => return None,
-                    };
-
-                    Some(RemoteInfo {
-                        name: name.clone(),
-                        url,
-                        url_type,
-                    })
-                })
-                .collect();
-            let changes = match repository.statuses(None) {
-                Ok(ss) => ss
-                    .iter()
-                    .map(|s| FileInfo {
-                        path: s.path().map(|s| s.to_string()),
-                        status: s.status().into(),
-                    })
-                    .collect::<Vec<FileInfo>>(),
-                Err(e) => {
-                    log::error!("{:?}", e);
-                    Vec::new()
-                }
-            };
+pub fn read_local_project(path: PathBuf, cache_dir: &Path) -> Option<LocalProject> {
+    if !path.join(".git").is_dir() {
+        let manifest = manifest::detect(&path);
+        return Some(LocalProject {
+            path,
+            git: None,
+            manifest,
+            languages: Vec::new(),
+        });
+    }
 
-            let mut commits = HashMap::<String, String>::new();
-            let branches = match repository.branches(Some(git2::BranchType::Local)) {
-                Ok(bs) => bs,
-                Err(e) => {
-                    log::warn!("{:?}", e);
-                    return None;
-                }
-            };
-            let branch_names = match all_branch_names(branches) {
-                Ok(bs) => bs,
-                Err(e) => {
-                    log::warn!("{:?}", e);
-                    Vec::new()
-                }
+    let mut repository = git2::Repository::open(path.clone())
+        .or_else(|e| {
+            log::error!("{:?}", e);
+            Err(e)
+        })
+        .ok()?;
+
+    let remote_names = match repository.remotes() {
+        Ok(rs) => rs
+            .iter()
+            .filter_map(|r| r.map(|r| r.to_string()))
+            .collect::<Vec<String>>(),
+        Err(e) => {
+            log::error!("{:?}", e);
+            Vec::new()
+        }
+    };
+    let remotes = remote_names
+        .iter()
+        .filter_map(|name| {
+            let remote = repository.find_remote(name).ok()?;
+            let url = remote.url()?.to_string();
+            let url_type = match url.get(0..4) {
+                Some("http") => RemoteUrlType::HTTP,
+                Some("git@") => RemoteUrlType::SSH,
+                _ => return None,
             };
 
-            for branch in branch_names {
-                let parsed = match repository.revparse_single(&branch) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        log::warn!("{:?}", e);
-                        continue;
-                    }
-                };
-                commits.insert(branch, parsed.id().to_string());
+            Some(RemoteInfo {
+                name: name.clone(),
+                url,
+                url_type,
+            })
+        })
+        .collect();
+    let changes = match repository.statuses(None) {
+        Ok(ss) => ss
+            .iter()
+            .map(|s| FileInfo {
+                path: s.path().map(|s| s.to_string()),
+                status: file_statuses(s.status()),
+            })
+            .collect::<Vec<FileInfo>>(),
+        Err(e) => {
+            log::error!("{:?}", e);
+            Vec::new()
+        }
+    };
+
+    let mut commits = HashMap::<String, String>::new();
+    let branches = match repository.branches(Some(git2::BranchType::Local)) {
+        Ok(bs) => bs,
+        Err(e) => {
+            log::warn!("{:?}", e);
+            return None;
+        }
+    };
+    let branch_names = match all_branch_names(branches) {
+        Ok(bs) => bs,
+        Err(e) => {
+            log::warn!("{:?}", e);
+            Vec::new()
+        }
+    };
+
+    for branch in branch_names {
+        let parsed = match repository.revparse_single(&branch) {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("{:?}", e);
+                continue;
             }
+        };
+        commits.insert(branch, parsed.id().to_string());
+    }
 
-            let git = Some(GitInfo {
-                changes,
-                remotes,
-                branch_commit: commits,
-            });
+    let stashes = match stash::list_stashes(&mut repository) {
+        Ok(ss) => ss,
+        Err(e) => {
+            log::warn!("{:?}", e);
+            Vec::new()
+        }
+    };
 
-            Some(LocalProject { path, git })
-        })
-        .collect::<Vec<LocalProject>>();
+    let tags = match tag::list_tags(&repository) {
+        Ok(ts) => ts,
+        Err(e) => {
+            log::warn!("{:?}", e);
+            Vec::new()
+        }
+    };
 
-    Ok(v)
+    let git = Some(GitInfo {
+        changes,
+        remotes,
+        branch_commit: commits,
+        stashes,
+        tags,
+    });
+
+    let languages = languages::language_stats(cache_dir, &repository).unwrap_or_else(|e| {
+        log::error!("{:?}", e);
+        Vec::new()
+    });
+    let manifest = manifest::detect(&path);
+    Some(LocalProject {
+        path,
+        git,
+        manifest,
+        languages,
+    })
 }
 
 pub async fn list_projects(
     github_token: &str,
     paths: &[PathBuf],
+    cache_dir: &Path,
     remote_params: &ListParameters,
 ) -> Result<Vec<Project>, Error> {
     let local_projects = paths
         .iter()
         .filter_map(|pth| {
-            list_local_projects(pth)
+            list_local_projects(pth, cache_dir)
                 .or_else(|e| {
                     log::error!("{:?}", e);
                     Err(e)
@@ -262,6 +315,10 @@ pub struct Project {
 }
 
 impl Project {
+    pub fn new(local: Option<LocalProject>, remote: Option<Repository>) -> Self {
+        Self { local, remote }
+    }
+
     pub fn local_name(&self) -> Option<String> {
         let local = match self.local.clone() {
             Some(l) => l,
@@ -300,11 +357,54 @@ impl Project {
         Some(commits)
     }
 
+    pub fn stashes(&self) -> Option<Vec<StashInfo>> {
+        let stashes = self.local.clone()?.git?.stashes;
+        Some(stashes)
+    }
+
+    pub fn tags(&self) -> Option<Vec<TagInfo>> {
+        let tags = self.local.clone()?.git?.tags;
+        Some(tags)
+    }
+
+    pub fn remotes(&self) -> Option<Vec<RemoteInfo>> {
+        let remotes = self.local.clone()?.git?.remotes;
+        Some(remotes)
+    }
+
+    pub fn changes(&self) -> Option<Vec<FileInfo>> {
+        let changes = self.local.clone()?.git?.changes;
+        Some(changes)
+    }
+
+    pub fn manifest(&self) -> Option<ManifestSummary> {
+        let manifest = self.local.clone()?.manifest;
+        Some(manifest)
+    }
+
+    pub fn languages(&self) -> Option<Vec<LanguageStats>> {
+        let languages = self.local.clone()?.languages;
+        Some(languages)
+    }
+
     pub fn number_of_changes(&self) -> Result<usize, Error> {
         let changes = self.get_git()?.changes;
         Ok(changes.len())
     }
 
+    pub fn local_path(&self) -> Result<PathBuf, Error> {
+        Ok(self.get_local()?.path)
+    }
+
+    pub fn open_repository(&self) -> Result<git2::Repository, Error> {
+        let path = self.get_local()?.path;
+        match git2::Repository::open(path) {
+            Ok(r) => Ok(r),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Err(Error::NoGit),
+            Err(e) => Err(Error::GitError(e)),
+        }
+    }
+
     fn get_local(&self) -> Result<LocalProject, Error> {
         match self.local.clone() {
             Some(l) => Ok(l),
@@ -324,6 +424,8 @@ impl Project {
 pub struct LocalProject {
     pub path: PathBuf,
     pub git: Option<GitInfo>,
+    pub manifest: ManifestSummary,
+    pub languages: Vec<LanguageStats>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -331,12 +433,14 @@ pub struct GitInfo {
     pub changes: Vec<FileInfo>,
     pub remotes: Vec<RemoteInfo>,
     pub branch_commit: HashMap<String, String>,
+    pub stashes: Vec<StashInfo>,
+    pub tags: Vec<TagInfo>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileInfo {
     pub path: Option<String>,
-    pub status: FileStatus,
+    pub status: Vec<FileStatus>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -369,24 +473,30 @@ pub enum FileStatus {
     Conflict,
 }
 
-impl From<git2::Status> for FileStatus {
-    fn from(status: git2::Status) -> Self {
-        match status {
-            git2::Status::CURRENT => FileStatus::Current,
-            git2::Status::INDEX_NEW => FileStatus::IndexNew,
-            git2::Status::INDEX_MODIFIED => FileStatus::IndexModified,
-            git2::Status::INDEX_DELETED => FileStatus::IndexDeleted,
-            git2::Status::INDEX_RENAMED => FileStatus::IndexRenamed,
-            git2::Status::INDEX_TYPECHANGE => FileStatus::IndexTypeChange,
-            git2::Status::WT_NEW => FileStatus::WorkTreeNew,
-            git2::Status::WT_MODIFIED => FileStatus::WorkTreeModified,
-            git2::Status::WT_DELETED => FileStatus::WorkTreeDeleted,
-            git2::Status::WT_TYPECHANGE => FileStatus::WorkTreeTypeChange,
-            git2::Status::WT_RENAMED => FileStatus::WorkTreeRenamed,
-            git2::Status::IGNORED => FileStatus::Ignored,
-            git2::Status::CONFLICTED => FileStatus::Conflict,
-            _ => unreachable!(),
-        }
+const STATUS_FLAGS: [(git2::Status, FileStatus); 12] = [
+    (git2::Status::INDEX_NEW, FileStatus::IndexNew),
+    (git2::Status::INDEX_MODIFIED, FileStatus::IndexModified),
+    (git2::Status::INDEX_DELETED, FileStatus::IndexDeleted),
+    (git2::Status::INDEX_RENAMED, FileStatus::IndexRenamed),
+    (git2::Status::INDEX_TYPECHANGE, FileStatus::IndexTypeChange),
+    (git2::Status::WT_NEW, FileStatus::WorkTreeNew),
+    (git2::Status::WT_MODIFIED, FileStatus::WorkTreeModified),
+    (git2::Status::WT_DELETED, FileStatus::WorkTreeDeleted),
+    (git2::Status::WT_TYPECHANGE, FileStatus::WorkTreeTypeChange),
+    (git2::Status::WT_RENAMED, FileStatus::WorkTreeRenamed),
+    (git2::Status::IGNORED, FileStatus::Ignored),
+    (git2::Status::CONFLICTED, FileStatus::Conflict),
+];
+
+pub fn file_statuses(status: git2::Status) -> Vec<FileStatus> {
+    let statuses = STATUS_FLAGS
+        .iter()
+        .filter(|(flag, _)| status.contains(*flag))
+        .map(|(_, s)| s.clone())
+        .collect::<Vec<FileStatus>>();
+    match statuses.is_empty() {
+        true => vec![FileStatus::Current],
+        false => statuses,
     }
 }
 
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use super::conventional;
+use crate::git::tag;
+use crate::github::user::Repository;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    TagError(#[from] tag::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ChangelogFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PullRequestLink {
+    pub number: u64,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChangelogEntry {
+    pub sha: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub breaking: bool,
+    pub pull_requests: Vec<PullRequestLink>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChangelogSection {
+    pub kind: String,
+    pub title: String,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Changelog {
+    pub from: Option<String>,
+    pub to: String,
+    pub sections: Vec<ChangelogSection>,
+}
+
+const SECTIONS: [(&str, &str); 11] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "Continuous Integration"),
+    ("style", "Style"),
+    ("chore", "Chores"),
+    ("revert", "Reverts"),
+];
+const OTHER: &str = "other";
+
+pub fn changelog(
+    repository: &git2::Repository,
+    from: Option<&str>,
+    to: Option<&str>,
+    remote: Option<&Repository>,
+) -> Result<Changelog, Error> {
+    let from = match (from, to) {
+        (Some(f), _) => Some(f.to_string()),
+        (None, Some(t)) => {
+            let target = repository.revparse_single(t)?.peel_to_commit()?.id();
+            tag::last_tag_before(repository, target)?
+        }
+        (None, None) => tag::commits_since_last_tag(repository)?.tag,
+    };
+    let to = to.unwrap_or("HEAD").to_string();
+
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    revwalk.push(repository.revparse_single(&to)?.peel_to_commit()?.id())?;
+    if let Some(from) = &from {
+        revwalk.hide(repository.revparse_single(from)?.peel_to_commit()?.id())?;
+    }
+
+    let pull_base = remote
+        .and_then(|r| r.url.clone())
+        .map(|u| String::from("https://") + u.as_str() + "/pull/");
+    let commits = revwalk
+        .map(|oid| repository.find_commit(oid?))
+        .collect::<Result<Vec<git2::Commit>, git2::Error>>()?;
+    let merged = merged_pull_requests(repository, &commits)?;
+
+    let mut sections: Vec<ChangelogSection> = Vec::new();
+    for commit in commits.iter().filter(|c| c.parent_count() <= 1) {
+        let message = commit.message().unwrap_or_default();
+        let summary = commit.summary().unwrap_or_default().to_string();
+        let parsed = conventional::parse(message);
+        let kind = match &parsed {
+            Some(c) if SECTIONS.iter().any(|(k, _)| *k == c.kind) => c.kind.clone(),
+            _ => String::from(OTHER),
+        };
+        let mut numbers = conventional::pull_request_numbers(message);
+        for number in merged.get(&commit.id()).into_iter().flatten() {
+            if !numbers.contains(number) {
+                numbers.push(*number);
+            }
+        }
+        let pull_requests = numbers
+            .into_iter()
+            .map(|number| PullRequestLink {
+                number,
+                url: pull_base.as_ref().map(|b| format!("{}{}", b, number)),
+            })
+            .collect();
+        let entry = match parsed {
+            Some(c) => ChangelogEntry {
+                sha: commit.id().to_string(),
+                scope: c.scope,
+                description: conventional::strip_pull_requests(&c.description),
+                breaking: c.breaking,
+                pull_requests,
+            },
+            None => ChangelogEntry {
+                sha: commit.id().to_string(),
+                scope: None,
+                description: conventional::strip_pull_requests(&summary),
+                breaking: false,
+                pull_requests,
+            },
+        };
+
+        match sections.iter_mut().find(|s| s.kind == kind) {
+            Some(section) => section.entries.push(entry),
+            None => sections.push(ChangelogSection {
+                title: section_title(&kind),
+                kind,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    sections.sort_by_key(|s| section_order(&s.kind));
+    for section in sections.iter_mut() {
+        section.entries.sort_by(|a, b| a.scope.cmp(&b.scope));
+    }
+    Ok(Changelog { from, to, sections })
+}
+
+fn merged_pull_requests(
+    repository: &git2::Repository,
+    commits: &[git2::Commit],
+) -> Result<HashMap<git2::Oid, Vec<u64>>, Error> {
+    let mut merged: HashMap<git2::Oid, Vec<u64>> = HashMap::new();
+    for merge in commits.iter().filter(|c| c.parent_count() > 1) {
+        let numbers = conventional::pull_request_numbers(merge.message().unwrap_or_default());
+        if numbers.is_empty() {
+            continue;
+        }
+        let mut revwalk = repository.revwalk()?;
+        for parent in merge.parent_ids().skip(1) {
+            revwalk.push(parent)?;
+        }
+        revwalk.hide(merge.parent_id(0)?)?;
+        for oid in revwalk {
+            let entry = merged.entry(oid?).or_default();
+            for number in numbers.iter() {
+                if !entry.contains(number) {
+                    entry.push(*number);
+                }
+            }
+        }
+    }
+    Ok(merged)
+}
+
+pub fn render(changelog: &Changelog, format: &ChangelogFormat) -> Result<String, Error> {
+    match format {
+        ChangelogFormat::Json => Ok(serde_json::to_string_pretty(changelog)?),
+        ChangelogFormat::Markdown => Ok(render_markdown(changelog)),
+    }
+}
+
+fn render_markdown(changelog: &Changelog) -> String {
+    let mut out = match &changelog.from {
+        Some(from) => format!("## {}...{}\n", from, changelog.to),
+        None => format!("## {}\n", changelog.to),
+    };
+    let breaking = changelog
+        .sections
+        .iter()
+        .flat_map(|s| s.entries.iter())
+        .filter(|e| e.breaking)
+        .collect::<Vec<&ChangelogEntry>>();
+    if !breaking.is_empty() {
+        out.push_str("\n### Breaking Changes\n\n");
+        for entry in breaking {
+            out.push_str(&render_entry(entry));
+        }
+    }
+    for section in changelog.sections.iter() {
+        out.push_str(&format!("\n### {}\n\n", section.title));
+        for entry in section.entries.iter() {
+            out.push_str(&render_entry(entry));
+        }
+    }
+    out
+}
+
+fn render_entry(entry: &ChangelogEntry) -> String {
+    let mut line = String::from("- ");
+    if let Some(scope) = &entry.scope {
+        line.push_str(&format!("**{}:** ", scope));
+    }
+    line.push_str(&entry.description);
+    for pr in entry.pull_requests.iter() {
+        match &pr.url {
+            Some(url) => line.push_str(&format!(" ([#{}]({}))", pr.number, url)),
+            None => line.push_str(&format!(" (#{})", pr.number)),
+        }
+    }
+    line.push_str(&format!(" ({})\n", &entry.sha[..7.min(entry.sha.len())]));
+    line
+}
+
+fn section_title(kind: &str) -> String {
+    SECTIONS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, t)| t.to_string())
+        .unwrap_or_else(|| String::from("Other Changes"))
+}
+
+fn section_order(kind: &str) -> usize {
+    SECTIONS
+        .iter()
+        .position(|(k, _)| *k == kind)
+        .unwrap_or(SECTIONS.len())
+}
@@ -0,0 +1,73 @@
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub breaking: bool,
+}
+
+pub fn parse(message: &str) -> Option<ConventionalCommit> {
+    let summary = message.lines().next()?.trim();
+    let (head, description) = summary.split_once(": ")?;
+    let (head, bang) = match head.strip_suffix('!') {
+        Some(h) => (h, true),
+        None => (head, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => (kind, Some(rest.strip_suffix(')')?.trim().to_string())),
+        None => (head, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let breaking = bang
+        || message
+            .lines()
+            .any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"));
+
+    Some(ConventionalCommit {
+        kind: kind.to_lowercase(),
+        scope: scope.filter(|s| !s.is_empty()),
+        description: description.trim().to_string(),
+        breaking,
+    })
+}
+
+pub fn pull_request_numbers(message: &str) -> Vec<u64> {
+    let mut numbers = Vec::new();
+    let mut rest = message;
+    while let Some(i) = rest.find('#') {
+        let preceded = rest[..i]
+            .chars()
+            .last()
+            .map(|c| c == '(' || c == ' ')
+            .unwrap_or(true);
+        rest = &rest[i + 1..];
+        let digits = rest
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>();
+        if !preceded || digits.is_empty() {
+            continue;
+        }
+        if let Ok(n) = digits.parse::<u64>() {
+            if !numbers.contains(&n) {
+                numbers.push(n);
+            }
+        }
+    }
+    numbers
+}
+
+pub fn strip_pull_requests(description: &str) -> String {
+    let mut out = description.trim_end();
+    while let Some(stripped) = out.strip_suffix(')') {
+        match stripped.rfind("(#") {
+            Some(i) if stripped[i + 2..].chars().all(|c| c.is_ascii_digit()) => {
+                out = stripped[..i].trim_end();
+            }
+            _ => break,
+        }
+    }
+    out.to_string()
+}
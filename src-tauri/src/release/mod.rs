@@ -0,0 +1,3 @@
+pub mod changelog;
+pub mod conventional;
+pub mod version;
@@ -0,0 +1,584 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::conventional;
+use crate::git::{commit, sync, tag};
+use crate::manifest::ProjectKind;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    TomlError(#[from] toml_edit::TomlError),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    SemverError(#[from] semver::Error),
+    #[error(transparent)]
+    TagError(#[from] tag::Error),
+    #[error(transparent)]
+    CommitError(#[from] commit::Error),
+    #[error(transparent)]
+    SyncError(#[from] sync::Error),
+    #[error("Repository has no working directory")]
+    NoWorkdir,
+    #[error("No Cargo.toml or package.json with a version was found")]
+    NoManifest,
+    #[error("No commits to release since {0}")]
+    NothingToRelease(String),
+    #[error("Worktree has uncommitted changes")]
+    DirtyWorktree,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestVersion {
+    pub path: String,
+    pub kind: ProjectKind,
+    pub name: Option<String>,
+    pub current: String,
+    pub next: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VersionPlan {
+    pub last_tag: Option<String>,
+    pub commits: usize,
+    pub suggested: Option<BumpLevel>,
+    pub level: Option<BumpLevel>,
+    pub current: String,
+    pub next: String,
+    pub tag_name: String,
+    pub manifests: Vec<ManifestVersion>,
+    pub files: Vec<String>,
+}
+
+const CARGO_DEPENDENCY_TABLES: [&[&str]; 4] = [
+    &["dependencies"],
+    &["dev-dependencies"],
+    &["build-dependencies"],
+    &["workspace", "dependencies"],
+];
+
+pub fn plan(repository: &git2::Repository, level: Option<BumpLevel>) -> Result<VersionPlan, Error> {
+    let workdir = repository.workdir().ok_or(Error::NoWorkdir)?;
+    let mut manifests = discover_manifests(workdir)?;
+    let primary = semver::Version::parse(&manifests.first().ok_or(Error::NoManifest)?.current)?;
+
+    let since = tag::commits_since_last_tag(repository)?;
+    let suggested = suggest_level(repository, since.tag.as_deref(), &primary)?;
+    let level = level.or(suggested);
+
+    for manifest in manifests.iter_mut() {
+        manifest.next = match level {
+            Some(level) => bump(&semver::Version::parse(&manifest.current)?, level).to_string(),
+            None => manifest.current.clone(),
+        };
+    }
+    let next = match level {
+        Some(level) => bump(&primary, level),
+        None => primary.clone(),
+    };
+
+    let mut files = manifests
+        .iter()
+        .map(|m| m.path.clone())
+        .collect::<Vec<String>>();
+    for lockfile in lockfiles(workdir, &manifests) {
+        if !files.contains(&lockfile) {
+            files.push(lockfile);
+        }
+    }
+
+    Ok(VersionPlan {
+        last_tag: since.tag,
+        commits: since.commits,
+        suggested,
+        level,
+        current: primary.to_string(),
+        tag_name: format!("v{}", next),
+        next: next.to_string(),
+        manifests,
+        files,
+    })
+}
+
+pub fn apply(repository: &git2::Repository, plan: &VersionPlan) -> Result<git2::Oid, Error> {
+    if plan.level.is_none() || plan.current == plan.next {
+        return Err(Error::NothingToRelease(
+            plan.last_tag
+                .clone()
+                .unwrap_or_else(|| String::from("HEAD")),
+        ));
+    }
+    if sync::is_dirty(repository)? {
+        return Err(Error::DirtyWorktree);
+    }
+    let workdir = repository.workdir().ok_or(Error::NoWorkdir)?;
+
+    for manifest in plan.manifests.iter() {
+        let path = workdir.join(&manifest.path);
+        let content = fs::read_to_string(&path)?;
+        let updated = match manifest.kind {
+            ProjectKind::Cargo | ProjectKind::CargoWorkspace => {
+                rewrite_cargo_manifest(&content, manifest, &plan.manifests)?
+            }
+            ProjectKind::Tauri => replace_json_version(
+                &content,
+                &["package", "version"],
+                &manifest.current,
+                &manifest.next,
+            ),
+            _ => replace_json_version(&content, &["version"], &manifest.current, &manifest.next),
+        };
+        fs::write(&path, updated)?;
+    }
+    for file in plan.files.iter() {
+        let path = workdir.join(file);
+        if file.ends_with("Cargo.lock") {
+            let content = fs::read_to_string(&path)?;
+            fs::write(&path, rewrite_cargo_lock(&content, &plan.manifests)?)?;
+        } else if file.ends_with("package-lock.json") {
+            let mut content = fs::read_to_string(&path)?;
+            let lock_dir = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+            for manifest in plan.manifests.iter().filter(|m| m.kind == ProjectKind::Npm) {
+                let dir = Path::new(&manifest.path)
+                    .parent()
+                    .and_then(|d| d.strip_prefix(lock_dir).ok());
+                let key = match dir {
+                    Some(d) => d.to_string_lossy().replace('\\', "/"),
+                    None => continue,
+                };
+                if key.is_empty() {
+                    content = replace_json_version(
+                        &content,
+                        &["version"],
+                        &manifest.current,
+                        &manifest.next,
+                    );
+                }
+                content = replace_json_version(
+                    &content,
+                    &["packages", &key, "version"],
+                    &manifest.current,
+                    &manifest.next,
+                );
+            }
+            fs::write(&path, content)?;
+        }
+    }
+
+    let mut index = repository.index()?;
+    for file in plan.files.iter() {
+        index.add_path(Path::new(file))?;
+    }
+    index.write()?;
+
+    let oid = commit::commit(repository, &format!("chore(release): {}", plan.tag_name))?;
+    tag::create_tag(
+        repository,
+        &plan.tag_name,
+        &oid.to_string(),
+        &format!("Release {}", plan.tag_name),
+    )?;
+    log::info!("[version::apply] {} -> {}", plan.current, plan.next);
+    Ok(oid)
+}
+
+pub fn bump(version: &semver::Version, level: BumpLevel) -> semver::Version {
+    let pre = !version.pre.is_empty();
+    match level {
+        BumpLevel::Major if pre && version.minor == 0 && version.patch == 0 => {
+            semver::Version::new(version.major, 0, 0)
+        }
+        BumpLevel::Major => semver::Version::new(version.major + 1, 0, 0),
+        BumpLevel::Minor if pre && version.patch == 0 => {
+            semver::Version::new(version.major, version.minor, 0)
+        }
+        BumpLevel::Minor => semver::Version::new(version.major, version.minor + 1, 0),
+        BumpLevel::Patch if pre => {
+            semver::Version::new(version.major, version.minor, version.patch)
+        }
+        BumpLevel::Patch => semver::Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+fn suggest_level(
+    repository: &git2::Repository,
+    since: Option<&str>,
+    current: &semver::Version,
+) -> Result<Option<BumpLevel>, Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push_head()?;
+    if let Some(since) = since {
+        revwalk.hide(repository.revparse_single(since)?.peel_to_commit()?.id())?;
+    }
+
+    let mut level = None;
+    for oid in revwalk {
+        let commit = repository.find_commit(oid?)?;
+        let candidate = match conventional::parse(commit.message().unwrap_or_default()) {
+            Some(c) if c.breaking => BumpLevel::Major,
+            Some(c) if c.kind == "feat" => BumpLevel::Minor,
+            _ => BumpLevel::Patch,
+        };
+        level = match (level, candidate) {
+            (Some(BumpLevel::Major), _) | (_, BumpLevel::Major) => Some(BumpLevel::Major),
+            (Some(BumpLevel::Minor), _) | (_, BumpLevel::Minor) => Some(BumpLevel::Minor),
+            _ => Some(BumpLevel::Patch),
+        };
+    }
+    if current.major == 0 && level == Some(BumpLevel::Major) {
+        level = Some(BumpLevel::Minor);
+    }
+    Ok(level)
+}
+
+fn discover_manifests(workdir: &Path) -> Result<Vec<ManifestVersion>, Error> {
+    let mut manifests = Vec::new();
+    for dir in [workdir.to_path_buf(), workdir.join("src-tauri")] {
+        cargo_manifests(workdir, &dir, &mut manifests)?;
+    }
+    npm_manifests(workdir, &mut manifests)?;
+    let tauri_conf = workdir.join("src-tauri").join("tauri.conf.json");
+    if tauri_conf.is_file() {
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&tauri_conf)?)?;
+        if let Some(version) = json["package"]["version"].as_str() {
+            manifests.push(manifest_version(
+                workdir,
+                &tauri_conf,
+                ProjectKind::Tauri,
+                json["package"]["productName"].as_str(),
+                version,
+            ));
+        }
+    }
+    Ok(manifests)
+}
+
+fn cargo_manifests(
+    workdir: &Path,
+    dir: &Path,
+    manifests: &mut Vec<ManifestVersion>,
+) -> Result<(), Error> {
+    let path = dir.join("Cargo.toml");
+    if !path.is_file() || manifests.iter().any(|m| workdir.join(&m.path) == path) {
+        return Ok(());
+    }
+    let document = fs::read_to_string(&path)?.parse::<toml_edit::Document>()?;
+
+    if let Some(version) = document
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("version"))
+        .and_then(|v| v.as_str())
+    {
+        manifests.push(manifest_version(
+            workdir,
+            &path,
+            ProjectKind::CargoWorkspace,
+            None,
+            version,
+        ));
+    }
+    if let Some(package) = document.get("package") {
+        if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+            let name = package.get("name").and_then(|n| n.as_str());
+            manifests.push(manifest_version(
+                workdir,
+                &path,
+                ProjectKind::Cargo,
+                name,
+                version,
+            ));
+        }
+    }
+
+    let members = document
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect::<Vec<&str>>())
+        .unwrap_or_default();
+    for member in expand_members(dir, &members) {
+        cargo_manifests(workdir, &member, manifests)?;
+    }
+    Ok(())
+}
+
+fn npm_manifests(workdir: &Path, manifests: &mut Vec<ManifestVersion>) -> Result<(), Error> {
+    let path = workdir.join("package.json");
+    if !path.is_file() {
+        return Ok(());
+    }
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    if let Some(version) = json["version"].as_str() {
+        manifests.push(manifest_version(
+            workdir,
+            &path,
+            ProjectKind::Npm,
+            json["name"].as_str(),
+            version,
+        ));
+    }
+
+    let workspaces = match &json["workspaces"] {
+        serde_json::Value::Array(a) => a.clone(),
+        serde_json::Value::Object(o) => o
+            .get("packages")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let workspaces = workspaces
+        .iter()
+        .filter_map(|w| w.as_str())
+        .collect::<Vec<&str>>();
+    for member in expand_members(workdir, &workspaces) {
+        let path = member.join("package.json");
+        if !path.is_file() {
+            continue;
+        }
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        if let Some(version) = json["version"].as_str() {
+            manifests.push(manifest_version(
+                workdir,
+                &path,
+                ProjectKind::Npm,
+                json["name"].as_str(),
+                version,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn expand_members(dir: &Path, members: &[&str]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for member in members {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                let mut children = fs::read_dir(dir.join(parent))
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .map(|e| e.path())
+                            .filter(|p| p.is_dir())
+                            .collect::<Vec<PathBuf>>()
+                    })
+                    .unwrap_or_default();
+                children.sort();
+                paths.extend(children);
+            }
+            None => paths.push(dir.join(member)),
+        }
+    }
+    paths
+}
+
+fn manifest_version(
+    workdir: &Path,
+    path: &Path,
+    kind: ProjectKind,
+    name: Option<&str>,
+    version: &str,
+) -> ManifestVersion {
+    ManifestVersion {
+        path: relative_path(workdir, path),
+        kind,
+        name: name.map(String::from),
+        current: version.to_string(),
+        next: version.to_string(),
+    }
+}
+
+fn relative_path(workdir: &Path, path: &Path) -> String {
+    path.strip_prefix(workdir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn lockfiles(workdir: &Path, manifests: &[ManifestVersion]) -> Vec<String> {
+    let mut files = Vec::new();
+    for manifest in manifests.iter() {
+        let lockfile = match manifest.kind {
+            ProjectKind::Cargo | ProjectKind::CargoWorkspace => "Cargo.lock",
+            ProjectKind::Npm => "package-lock.json",
+            _ => continue,
+        };
+        let mut dir = workdir.join(&manifest.path);
+        while dir.pop() && dir.starts_with(workdir) {
+            let path = dir.join(lockfile);
+            if path.is_file() {
+                files.push(relative_path(workdir, &path));
+                break;
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn table_like_mut<'a>(
+    document: &'a mut toml_edit::Document,
+    keys: &[&str],
+) -> Option<&'a mut dyn toml_edit::TableLike> {
+    let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+    for key in keys {
+        table = table.get_mut(key)?.as_table_like_mut()?;
+    }
+    Some(table)
+}
+
+fn set_version(item: &mut toml_edit::Item, version: &str) {
+    if let Some(value) = item.as_value_mut() {
+        let decor = value.decor().clone();
+        *value = toml_edit::Value::from(version);
+        *value.decor_mut() = decor;
+    }
+}
+
+fn rewrite_cargo_manifest(
+    content: &str,
+    manifest: &ManifestVersion,
+    manifests: &[ManifestVersion],
+) -> Result<String, Error> {
+    let mut document = content.parse::<toml_edit::Document>()?;
+    let package = match manifest.kind {
+        ProjectKind::CargoWorkspace => table_like_mut(&mut document, &["workspace", "package"]),
+        _ => table_like_mut(&mut document, &["package"]),
+    };
+    if let Some(item) = package.and_then(|p| p.get_mut("version")) {
+        set_version(item, &manifest.next);
+    }
+
+    for keys in CARGO_DEPENDENCY_TABLES.iter() {
+        let table = match table_like_mut(&mut document, keys) {
+            Some(t) => t,
+            None => continue,
+        };
+        for (name, dependency) in table.iter_mut() {
+            let dependency = match dependency.as_table_like_mut() {
+                Some(d) if d.contains_key("path") => d,
+                _ => continue,
+            };
+            let name = dependency
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or_else(|| name.get())
+                .to_string();
+            let bumped = manifests
+                .iter()
+                .find(|m| m.kind == ProjectKind::Cargo && m.name.as_deref() == Some(name.as_str()));
+            if let (Some(bumped), Some(requirement)) = (bumped, dependency.get_mut("version")) {
+                if let Some(prefix) = requirement
+                    .as_str()
+                    .and_then(|r| r.strip_suffix(bumped.current.as_str()))
+                {
+                    let next = format!("{}{}", prefix, bumped.next);
+                    set_version(requirement, &next);
+                }
+            }
+        }
+    }
+    Ok(document.to_string())
+}
+
+fn rewrite_cargo_lock(content: &str, manifests: &[ManifestVersion]) -> Result<String, Error> {
+    let mut document = content.parse::<toml_edit::Document>()?;
+    if let Some(packages) = document
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    {
+        for package in packages.iter_mut() {
+            let name = package.get("name").and_then(|n| n.as_str());
+            let version = package.get("version").and_then(|v| v.as_str());
+            let bumped = manifests.iter().find(|m| {
+                matches!(m.kind, ProjectKind::Cargo)
+                    && m.name.as_deref() == name
+                    && Some(m.current.as_str()) == version
+                    && package.get("source").is_none()
+            });
+            if let (Some(bumped), Some(item)) = (bumped, package.get_mut("version")) {
+                set_version(item, &bumped.next);
+            }
+        }
+    }
+    Ok(document.to_string())
+}
+
+fn replace_json_version(content: &str, keys: &[&str], current: &str, next: &str) -> String {
+    let old_value = format!("\"{}\"", current);
+    let bytes = content.as_bytes();
+    let mut path: Vec<Option<String>> = Vec::new();
+    let mut objects: Vec<bool> = Vec::new();
+    let mut key: Option<String> = None;
+    let mut expect_key = false;
+    let mut found = None;
+    let mut i = 0;
+    while i < bytes.len() && found.is_none() {
+        match bytes[i] {
+            b'"' => {
+                let mut end = i + 1;
+                while end < bytes.len() && bytes[end] != b'"' {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                let raw = &content[i..(end + 1).min(content.len())];
+                if expect_key {
+                    key = serde_json::from_str(raw).ok();
+                    expect_key = false;
+                } else if objects.last() == Some(&true)
+                    && raw == old_value
+                    && path.len() + 1 == keys.len()
+                    && path
+                        .iter()
+                        .chain(Some(&key))
+                        .zip(keys)
+                        .all(|(k, expected)| k.as_deref() == Some(*expected))
+                {
+                    found = Some(i);
+                }
+                i = end;
+            }
+            b'{' | b'[' => {
+                if let Some(object) = objects.last() {
+                    path.push(if *object { key.take() } else { None });
+                }
+                objects.push(bytes[i] == b'{');
+                expect_key = bytes[i] == b'{';
+            }
+            b'}' | b']' => {
+                objects.pop();
+                if !objects.is_empty() {
+                    path.pop();
+                }
+            }
+            b',' => expect_key = objects.last() == Some(&true),
+            _ => (),
+        }
+        i += 1;
+    }
+    match found {
+        Some(start) => format!(
+            "{}\"{}\"{}",
+            &content[..start],
+            next,
+            &content[start + old_value.len()..]
+        ),
+        None => content.to_string(),
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::{offset::Utc, SecondsFormat};
+use uuid::Uuid;
+
+use crate::{
+    licenses,
+    lockfile::{self, Ecosystem, LockedPackage},
+    project::Project,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("No local project")]
+    NoLocalProject,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum SbomFormat {
+    CycloneDx,
+    Spdx,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SbomSubject {
+    pub name: String,
+    pub version: Option<String>,
+    pub commit: Option<String>,
+    pub remote: Option<String>,
+    pub license: Option<String>,
+}
+
+struct Component {
+    ecosystem: Ecosystem,
+    package: LockedPackage,
+    license: Option<String>,
+    purl: String,
+    dependencies: Vec<String>,
+}
+
+const TOOL_NAME: &str = "mgmt";
+const NO_ASSERTION: &str = "NOASSERTION";
+
+pub fn subject(project: &Project) -> Result<SbomSubject, Error> {
+    let path = project.local_path().map_err(|_| Error::NoLocalProject)?;
+    let manifest = project.manifest().unwrap_or_default();
+    let commit = project
+        .open_repository()
+        .ok()
+        .and_then(|r| r.head().ok()?.target())
+        .map(|oid| oid.to_string());
+    let remotes = project.remotes().unwrap_or_default();
+    let remote = remotes
+        .iter()
+        .find(|r| r.name == "origin")
+        .or_else(|| remotes.first())
+        .map(|r| strip_userinfo(&r.url))
+        .or_else(|| project.remote.as_ref().and_then(|r| r.url.clone()));
+    let github = project.remote.as_ref().and_then(|r| r.license.clone());
+
+    Ok(SbomSubject {
+        name: manifest
+            .name
+            .or_else(|| project.local_name())
+            .unwrap_or_default(),
+        version: manifest.version,
+        commit,
+        remote,
+        license: licenses::project_license(&path, github.as_deref()).primary(),
+    })
+}
+
+pub fn generate(path: &Path, subject: &SbomSubject, format: &SbomFormat) -> Result<String, Error> {
+    let mut components: Vec<Component> = Vec::new();
+    let mut roots: Vec<String> = Vec::new();
+    for lockfile in lockfile::lockfiles(path) {
+        let ecosystem = lockfile.ecosystem;
+        for dependency in lockfile.roots.iter() {
+            let purl = purl(ecosystem, &dependency.name, &dependency.version);
+            if !roots.contains(&purl) {
+                roots.push(purl);
+            }
+        }
+        for package in lockfile.packages {
+            if ecosystem == Ecosystem::Cargo && package.source.is_none() {
+                continue;
+            }
+            let dependencies = package
+                .dependencies
+                .iter()
+                .map(|d| purl(ecosystem, &d.name, &d.version))
+                .collect::<Vec<String>>();
+            let purl = purl(ecosystem, &package.name, &package.version);
+            match components.iter_mut().find(|c| c.purl == purl) {
+                Some(component) => {
+                    for dependency in dependencies {
+                        if !component.dependencies.contains(&dependency) {
+                            component.dependencies.push(dependency);
+                        }
+                    }
+                }
+                None => components.push(Component {
+                    ecosystem,
+                    license: licenses::package_license(path, ecosystem, &package),
+                    purl,
+                    dependencies,
+                    package,
+                }),
+            }
+        }
+    }
+    let purls = components
+        .iter()
+        .map(|c| c.purl.clone())
+        .collect::<HashSet<String>>();
+    for component in components.iter_mut() {
+        component.dependencies.retain(|d| purls.contains(d));
+        component.dependencies.sort();
+    }
+    roots.retain(|r| purls.contains(r));
+    roots.sort();
+    components.sort_by(|a, b| a.purl.cmp(&b.purl));
+
+    let document = match format {
+        SbomFormat::CycloneDx => cyclonedx(subject, &components, &roots),
+        SbomFormat::Spdx => spdx(subject, &components, &roots),
+    };
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+fn cyclonedx(
+    subject: &SbomSubject,
+    components: &[Component],
+    roots: &[String],
+) -> serde_json::Value {
+    let subject_ref = format!("urn:uuid:{}", Uuid::new_v4());
+    let mut metadata = serde_json::json!({
+        "timestamp": timestamp(),
+        "tools": {
+            "components": [{
+                "type": "application",
+                "name": TOOL_NAME,
+                "version": env!("CARGO_PKG_VERSION"),
+            }],
+        },
+        "component": {
+            "type": "application",
+            "bom-ref": subject_ref,
+            "name": subject.name,
+        },
+    });
+    let component = &mut metadata["component"];
+    if let Some(version) = &subject.version {
+        component["version"] = serde_json::json!(version);
+    }
+    if let Some(license) = &subject.license {
+        component["licenses"] = cyclonedx_license(license);
+    }
+    if let Some(remote) = &subject.remote {
+        component["externalReferences"] = serde_json::json!([{ "type": "vcs", "url": remote }]);
+    }
+    if let Some(commit) = &subject.commit {
+        component["properties"] = serde_json::json!([{ "name": "git:commit", "value": commit }]);
+    }
+
+    let mut dependencies = vec![serde_json::json!({ "ref": subject_ref, "dependsOn": roots })];
+    dependencies.extend(
+        components
+            .iter()
+            .map(|c| serde_json::json!({ "ref": c.purl, "dependsOn": c.dependencies })),
+    );
+    let components = components
+        .iter()
+        .map(|c| {
+            let mut component = serde_json::json!({
+                "type": "library",
+                "bom-ref": c.purl,
+                "name": c.package.name,
+                "version": c.package.version,
+                "purl": c.purl,
+            });
+            if let Some(license) = &c.license {
+                component["licenses"] = cyclonedx_license(license);
+            }
+            if let Some(checksum) = cargo_checksum(c) {
+                component["hashes"] =
+                    serde_json::json!([{ "alg": "SHA-256", "content": checksum }]);
+            }
+            component
+        })
+        .collect::<Vec<serde_json::Value>>();
+
+    serde_json::json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", Uuid::new_v4()),
+        "version": 1,
+        "metadata": metadata,
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+fn spdx(subject: &SbomSubject, components: &[Component], roots: &[String]) -> serde_json::Value {
+    let remote = subject.remote.as_ref().map(|r| match r.contains("://") {
+        true => r.clone(),
+        false => format!("ssh://{}", r.replacen(':', "/", 1)),
+    });
+    let download = match (&remote, &subject.commit) {
+        (Some(remote), Some(commit)) => format!("git+{}@{}", remote, commit),
+        (Some(remote), None) => format!("git+{}", remote),
+        _ => NO_ASSERTION.to_string(),
+    };
+    let mut extracted: HashMap<String, String> = HashMap::new();
+    let mut root = serde_json::json!({
+        "SPDXID": "SPDXRef-Project",
+        "name": subject.name,
+        "downloadLocation": download,
+        "filesAnalyzed": false,
+        "licenseConcluded": NO_ASSERTION,
+        "licenseDeclared": spdx_license(subject.license.as_deref(), &mut extracted),
+        "copyrightText": NO_ASSERTION,
+    });
+    if let Some(version) = &subject.version {
+        root["versionInfo"] = serde_json::json!(version);
+    }
+    if let Some(commit) = &subject.commit {
+        root["sourceInfo"] = serde_json::json!(format!("git commit {}", commit));
+    }
+
+    let mut packages = vec![root];
+    let mut relationships = vec![serde_json::json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Project",
+    })];
+    let ids = components
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let id = format!(
+                "SPDXRef-Package-{}-{}",
+                i + 1,
+                spdx_id(&format!("{}-{}", c.package.name, c.package.version))
+            );
+            (c.purl.as_str(), id)
+        })
+        .collect::<HashMap<&str, String>>();
+    let depends_on = |from: &str, to: &String| {
+        serde_json::json!({
+            "spdxElementId": from,
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": ids[to.as_str()],
+        })
+    };
+    relationships.extend(roots.iter().map(|r| depends_on("SPDXRef-Project", r)));
+    for c in components.iter() {
+        let id = &ids[c.purl.as_str()];
+        let mut package = serde_json::json!({
+            "SPDXID": id,
+            "name": c.package.name,
+            "versionInfo": c.package.version,
+            "downloadLocation": download_location(c),
+            "filesAnalyzed": false,
+            "licenseConcluded": NO_ASSERTION,
+            "licenseDeclared": spdx_license(c.license.as_deref(), &mut extracted),
+            "copyrightText": NO_ASSERTION,
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": c.purl,
+            }],
+        });
+        if let Some(checksum) = cargo_checksum(c) {
+            package["checksums"] =
+                serde_json::json!([{ "algorithm": "SHA256", "checksumValue": checksum }]);
+        }
+        packages.push(package);
+        relationships.extend(c.dependencies.iter().map(|d| depends_on(id, d)));
+    }
+
+    let mut extracted = extracted
+        .into_iter()
+        .map(|(id, text)| {
+            serde_json::json!({ "licenseId": id, "name": text, "extractedText": text })
+        })
+        .collect::<Vec<serde_json::Value>>();
+    extracted.sort_by(|a, b| a["licenseId"].as_str().cmp(&b["licenseId"].as_str()));
+
+    let mut document = serde_json::json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": subject.name,
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}",
+            spdx_id(&subject.name),
+            Uuid::new_v4()
+        ),
+        "creationInfo": {
+            "created": timestamp(),
+            "creators": [format!("Tool: {}-{}", TOOL_NAME, env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    });
+    if !extracted.is_empty() {
+        document["hasExtractedLicensingInfos"] = serde_json::json!(extracted);
+    }
+    document
+}
+
+fn cyclonedx_license(license: &str) -> serde_json::Value {
+    match spdx_expression(license) {
+        Some(expression) => serde_json::json!([{ "expression": expression }]),
+        None => serde_json::json!([{ "license": { "name": license } }]),
+    }
+}
+
+fn spdx_license(license: Option<&str>, extracted: &mut HashMap<String, String>) -> String {
+    let license = match license {
+        Some(l) if !l.trim().is_empty() => l,
+        _ => return NO_ASSERTION.to_string(),
+    };
+    if let Some(expression) = spdx_expression(license) {
+        return expression;
+    }
+    let id = format!("LicenseRef-{}", spdx_id(license.trim()));
+    extracted.insert(id.clone(), license.to_string());
+    id
+}
+
+fn spdx_expression(license: &str) -> Option<String> {
+    let spaced = license
+        .replace('/', " OR ")
+        .replace('(', " ( ")
+        .replace(')', " ) ");
+    let mut depth = 0;
+    let mut expect_term = true;
+    let mut after_with = false;
+    let mut normalized: Vec<String> = Vec::new();
+    for token in spaced.split_whitespace() {
+        let upper = token.to_uppercase();
+        match upper.as_str() {
+            "(" if expect_term && !after_with => depth += 1,
+            ")" if !expect_term && depth > 0 => depth -= 1,
+            "AND" | "OR" if !expect_term => expect_term = true,
+            "WITH" if !expect_term && !after_with => {
+                expect_term = true;
+                after_with = true;
+            }
+            _ if expect_term && is_license_id(token) => {
+                expect_term = false;
+                after_with = false;
+                normalized.push(token.to_string());
+                continue;
+            }
+            _ => return None,
+        }
+        normalized.push(upper);
+    }
+    if expect_term || depth != 0 {
+        return None;
+    }
+    Some(normalized.join(" ").replace("( ", "(").replace(" )", ")"))
+}
+
+fn is_license_id(token: &str) -> bool {
+    let id = token.strip_suffix('+').unwrap_or(token);
+    let id = match id.split_once(':') {
+        Some((document, license)) if document.starts_with("DocumentRef-") => license,
+        Some(_) => return false,
+        None => id,
+    };
+    !id.is_empty()
+        && !matches!(id.to_uppercase().as_str(), "AND" | "OR" | "WITH")
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+fn purl(ecosystem: Ecosystem, name: &str, version: &str) -> String {
+    match ecosystem {
+        Ecosystem::Cargo => format!("pkg:cargo/{}@{}", name, version),
+        Ecosystem::Npm => format!("pkg:npm/{}@{}", name.replace('@', "%40"), version),
+    }
+}
+
+fn strip_userinfo(url: &str) -> String {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((s, r)) => (Some(s), r),
+        None => (None, url),
+    };
+    let end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..end].rfind('@') {
+        Some(i) => &rest[i + 1..],
+        None => rest,
+    };
+    match scheme {
+        Some(scheme) => format!("{}://{}", scheme, rest),
+        None => rest.to_string(),
+    }
+}
+
+fn cargo_checksum(component: &Component) -> Option<&String> {
+    match component.ecosystem {
+        Ecosystem::Cargo => component.package.checksum.as_ref(),
+        Ecosystem::Npm => None,
+    }
+}
+
+fn download_location(component: &Component) -> String {
+    match (&component.ecosystem, &component.package.source) {
+        (Ecosystem::Npm, Some(source)) if source.starts_with("http") => source.clone(),
+        (Ecosystem::Cargo, Some(source)) if source.contains("crates.io") => format!(
+            "https://crates.io/api/v1/crates/{}/{}/download",
+            component.package.name, component.package.version
+        ),
+        (Ecosystem::Cargo, Some(source)) if source.starts_with("git+") => source.clone(),
+        _ => NO_ASSERTION.to_string(),
+    }
+}
+
+fn spdx_id(value: &str) -> String {
+    value
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                true => c,
+                false => '-',
+            },
+        )
+        .collect()
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
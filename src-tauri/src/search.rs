@@ -0,0 +1,221 @@
+use std::{
+    fs,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use regex::{Regex, RegexBuilder};
+use uuid::Uuid;
+
+use crate::project::Project;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    GitError(#[from] git2::Error),
+    #[error(transparent)]
+    RegexError(#[from] regex::Error),
+    #[error(transparent)]
+    PatternError(#[from] glob::PatternError),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SearchQuery {
+    pub pattern: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SearchMatch {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectMatches {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub files: usize,
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectError {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SearchSummary {
+    pub projects: usize,
+    pub files: usize,
+    pub matches: usize,
+    pub truncated: bool,
+    pub cancelled: bool,
+    pub errors: Vec<ProjectError>,
+}
+
+const DEFAULT_MAX_RESULTS: usize = 2000;
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_TEXT_CHARS: usize = 300;
+const BINARY_PROBE_BYTES: usize = 8000;
+
+pub struct Matcher {
+    regex: Regex,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl Matcher {
+    pub fn new(query: &SearchQuery) -> Result<Self, Error> {
+        let pattern = if query.regex {
+            query.pattern.clone()
+        } else {
+            regex::escape(&query.pattern)
+        };
+        let pattern = if query.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!query.case_sensitive)
+            .build()?;
+        let include = query
+            .include
+            .iter()
+            .map(|p| glob::Pattern::new(p))
+            .collect::<Result<Vec<glob::Pattern>, glob::PatternError>>()?;
+        let exclude = query
+            .exclude
+            .iter()
+            .map(|p| glob::Pattern::new(p))
+            .collect::<Result<Vec<glob::Pattern>, glob::PatternError>>()?;
+        Ok(Self {
+            regex,
+            include,
+            exclude,
+        })
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(path)))
+            && !self.exclude.iter().any(|p| p.matches(path))
+    }
+}
+
+pub fn run<F>(
+    query: &SearchQuery,
+    targets: Vec<(Uuid, Project)>,
+    cancel: &AtomicBool,
+    mut progress: F,
+) -> Result<SearchSummary, Error>
+where
+    F: FnMut(&ProjectMatches),
+{
+    let matcher = Matcher::new(query)?;
+    let max_results = query.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let mut summary = SearchSummary::default();
+
+    for (id, project) in targets {
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        if summary.matches >= max_results {
+            summary.truncated = true;
+            break;
+        }
+        let repository = match project.open_repository() {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        let result =
+            match search_repository(&matcher, &repository, max_results - summary.matches, cancel) {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!("[search] {:?}: {:?}", project.local_name(), e);
+                    summary.errors.push(ProjectError {
+                        id,
+                        name: project.local_name(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+        summary.projects += 1;
+        summary.files += result.0;
+        summary.matches += result.1.len();
+        summary.truncated |= summary.matches >= max_results;
+        if result.1.is_empty() {
+            continue;
+        }
+        progress(&ProjectMatches {
+            id,
+            name: project.local_name(),
+            files: result.0,
+            matches: result.1,
+        });
+    }
+    summary.cancelled = cancel.load(Ordering::SeqCst);
+    Ok(summary)
+}
+
+fn search_repository(
+    matcher: &Matcher,
+    repository: &git2::Repository,
+    limit: usize,
+    cancel: &AtomicBool,
+) -> Result<(usize, Vec<SearchMatch>), Error> {
+    let workdir = match repository.workdir() {
+        Some(w) => w,
+        None => return Ok((0, Vec::new())),
+    };
+    let index = repository.index()?;
+    let mut files = 0;
+    let mut matches = Vec::new();
+    for entry in index.iter() {
+        if matches.len() >= limit || cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        if !matcher.matches_path(&path) {
+            continue;
+        }
+        let full_path = workdir.join(&path);
+        match fs::metadata(&full_path) {
+            Ok(m) if m.is_file() && m.len() <= MAX_FILE_BYTES => (),
+            _ => continue,
+        }
+        let content = match fs::read(&full_path) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        if content.iter().take(BINARY_PROBE_BYTES).any(|b| *b == 0) {
+            continue;
+        }
+        files += 1;
+
+        let content = String::from_utf8_lossy(&content);
+        for (n, line) in content.lines().enumerate() {
+            if let Some(m) = matcher.regex.find(line) {
+                matches.push(SearchMatch {
+                    path: path.clone(),
+                    line: n + 1,
+                    column: line[..m.start()].chars().count() + 1,
+                    text: line.trim().chars().take(MAX_TEXT_CHARS).collect(),
+                });
+                if matches.len() >= limit {
+                    break;
+                }
+            }
+        }
+    }
+    Ok((files, matches))
+}
@@ -3,24 +3,41 @@ import './App.css'
 import { invoke } from '@tauri-apps/api'
 import ProjectCard from './ProjectCard'
 
+const KINDS = ["Cargo", "CargoWorkspace", "Npm", "Yarn", "Pnpm", "Python", "Go", "Tauri", "Docker", "DockerCompose"]
+
 function App() {
 
   const [reloading, setReloading] = createSignal(true)
   const [ids, setIDs] = createSignal<string[]>([])
+  const [kinds, setKinds] = createSignal<string[]>([])
   createEffect(() => {
     const r = reloading();
     if (!r) return;
     invoke("update_projects").then(() => {
-      invoke<string[]>("project_ids").then((res) => setIDs(res)).catch((err) => console.error(err));
+      invoke<string[]>("project_ids", {kinds: kinds()}).then((res) => setIDs(res)).catch((err) => console.error(err));
     }).catch((err) => console.error(err));
 
     setReloading(false);
   }, [reloading])
+
+  const toggleKind = (kind: string) => {
+    const k = kinds().includes(kind) ? kinds().filter((k) => k !== kind) : [...kinds(), kind];
+    setKinds(k);
+    invoke<string[]>("project_ids", {kinds: k}).then((res) => setIDs(res)).catch((err) => console.error(err));
+  }
   return (
     <>
       <div>
         <button onClick={() => setReloading(true)} disabled={reloading()}>Refresh</button>
       </div>
+      <div class='kind-filter'>
+        <For each={KINDS}>{(kind) =>
+          <label>
+            <input type='checkbox' checked={kinds().includes(kind)} onChange={() => toggleKind(kind)} />
+            {kind}
+          </label>
+        }</For>
+      </div>
       <div class='container'>
         <For each={ids()}>{(id) => <ProjectCard id={id} />}</For>
       </div>
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api"
-import { Accessor, For, Show, createEffect, createSignal, onMount } from "solid-js"
+import { listen } from "@tauri-apps/api/event"
+import { Accessor, For, Show, createEffect, createSignal, onCleanup, onMount } from "solid-js"
 import "./ProjectCard.css"
 import { nameFormat } from "./utils"
 
@@ -11,11 +12,40 @@ export default (props: {
   
   return <div class="project-card">
     <NameEl id={id} />
+    <KindsEl id={id} />
     {/* <span>{remoteName()}</span>|<span>{localName()}</span> */}
     <RemoteEl id={id} />
   </div>
 }
 
+type Manifest = {
+  kinds: string[],
+  manifests: string[],
+  name?: string,
+  version?: string,
+  toolchains: {tool: string, version: string, source: string}[],
+}
+const KindsEl = (props: {
+  id: string,
+}) => {
+
+  const {id} = props;
+  const [manifest, setManifest] = createSignal<Manifest>();
+
+  onMount(() => {
+    invoke<Manifest | undefined>("project_manifest", {id: id})
+      .then((res) => setManifest(res))
+      .catch((err) => console.log(err));
+  })
+
+  return <div class="kinds-el">
+    <For each={manifest()?.kinds ?? []}>{(kind) => <span>{kind}</span>}</For>
+    <For each={manifest()?.toolchains ?? []}>
+      {(t) => <span title={t.source}>{t.tool} {t.version}</span>}
+    </For>
+  </div>
+}
+
 type Relation = "Ahead" | "Behind" | "Same" | "Null"
 const RemoteEl = (props: {
   id: string,
@@ -107,5 +137,67 @@ const NameEl = (props: {
     <Show when={!localName() && !remoteName()}>
       <span>Unknown</span>
     </Show>
+    <Show when={!localName() && remoteName()}>
+      <CloneEl id={id} onCloned={(name) => setLocalName(nameFormat(name))} />
+    </Show>
   </div>
 }
+
+type UrlType = "SSH" | "HTTP"
+
+type TransferProgress = {
+  total_objects: number,
+  received_objects: number,
+  indexed_objects: number,
+  received_bytes: number,
+}
+const CloneEl = (props: {
+  id: string,
+  onCloned: (name: string) => void,
+}) => {
+
+  const {id} = props;
+  const [cloning, setCloning] = createSignal(false);
+  const [progress, setProgress] = createSignal<TransferProgress>();
+  const [dirs, setDirs] = createSignal<string[]>([]);
+  const [root, setRoot] = createSignal<string>();
+  const [urlType, setUrlType] = createSignal<UrlType>("SSH");
+
+  onMount(() => {
+    invoke<string[]>("project_dirs")
+      .then((res) => {
+        setDirs(res);
+        setRoot(res[0]);
+      })
+      .catch((err) => console.log(err));
+    const unlisten = listen<{id: string, progress: TransferProgress}>("clone-progress", (event) => {
+      if (event.payload.id === id) setProgress(event.payload.progress);
+    });
+    onCleanup(() => unlisten.then((f) => f()));
+  })
+
+  const toggleUrlType = () => setUrlType(urlType() === "SSH" ? "HTTP" : "SSH");
+
+  const clone = () => {
+    setCloning(true);
+    invoke("clone_project", {id: id, root: root(), urlType: urlType()})
+      .then(() => invoke<string | undefined>("project_local_name", {id: id}))
+      .then((res) => res && props.onCloned(res))
+      .catch((err) => console.log(err))
+      .finally(() => setCloning(false));
+  }
+
+  return <Show when={cloning()} fallback={
+    <div class="clone-el">
+      <select value={root()} onChange={(e) => setRoot(e.currentTarget.value)}>
+        <For each={dirs()}>{(dir) => <option value={dir}>{dir}</option>}</For>
+      </select>
+      <button onClick={toggleUrlType}>{urlType() === "SSH" ? "SSH" : "HTTPS"}</button>
+      <button onClick={clone} disabled={!root()}>Clone</button>
+    </div>
+  }>
+    <span>
+      {progress() ? `${progress()!.received_objects}/${progress()!.total_objects}` : "..."}
+    </span>
+  </Show>
+}